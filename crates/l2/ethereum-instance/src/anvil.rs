@@ -0,0 +1,271 @@
+use ethers::prelude::SignerMiddleware;
+use ethers::providers::{Http, Provider};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::Address;
+use std::io::{BufRead, BufReader};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::{Error, EthereumClient, ANVIL_DEFAULT_CHAIN_ID, POLLING_INTERVAL_MS};
+
+/// Time Anvil has to report it is listening
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Configures and spawns a new Anvil instance.
+/// Options left unset fall back to Anvil defaults.
+#[derive(Debug, Clone, Default)]
+pub struct AnvilBuilder {
+    anvil_path: Option<PathBuf>,
+    port: Option<u16>,
+    chain_id: Option<u64>,
+    block_time: Option<u64>,
+    accounts: Option<u32>,
+    mnemonic: Option<String>,
+    load_state: Option<PathBuf>,
+    dump_state: Option<PathBuf>,
+    gas_limit: Option<u64>,
+}
+
+impl AnvilBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Path to the Anvil binary (a bare name being looked up in `$PATH`), if not specified will use:
+    ///     - ${ANVIL_PATH} environment variable (if set)
+    ///     - ~/.foundry/bin/anvil (default)
+    pub fn anvil_path(mut self, anvil_path: impl Into<PathBuf>) -> Self {
+        self.anvil_path = Some(anvil_path.into());
+        self
+    }
+
+    /// Port to listen on, a random unused port is picked if not specified
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    pub fn chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Block time in seconds, blocks are mined instantly on every transaction if not specified
+    pub fn block_time(mut self, block_time: u64) -> Self {
+        self.block_time = Some(block_time);
+        self
+    }
+
+    /// Number of prefunded dev accounts
+    pub fn accounts(mut self, accounts: u32) -> Self {
+        self.accounts = Some(accounts);
+        self
+    }
+
+    /// BIP-39 mnemonic the dev accounts are derived from
+    pub fn mnemonic(mut self, mnemonic: impl Into<String>) -> Self {
+        self.mnemonic = Some(mnemonic.into());
+        self
+    }
+
+    /// Initializes the chain from a state file previously written with [`Self::dump_state`]
+    pub fn load_state(mut self, path: impl Into<PathBuf>) -> Self {
+        self.load_state = Some(path.into());
+        self
+    }
+
+    /// Writes the chain state to a file when Anvil exits
+    pub fn dump_state(mut self, path: impl Into<PathBuf>) -> Self {
+        self.dump_state = Some(path.into());
+        self
+    }
+
+    /// Block gas limit
+    pub fn gas_limit(mut self, gas_limit: u64) -> Self {
+        self.gas_limit = Some(gas_limit);
+        self
+    }
+
+    /// Spawns Anvil and returns a client configured with the first prefunded account.
+    pub fn spawn(self) -> Result<EthereumClient, Error> {
+        let anvil_path: PathBuf = match self.anvil_path {
+            Some(anvil_path) => anvil_path,
+            None => match std::env::var("ANVIL_PATH") {
+                Ok(anvil_path) => anvil_path.into(),
+                Err(_) => dirs::home_dir()
+                    .ok_or_else(|| Error::AnvilNotFound("~/.foundry/bin/anvil".into()))?
+                    .join(".foundry/bin/anvil"),
+            },
+        };
+
+        let anvil_path = resolve_binary(&anvil_path).ok_or(Error::AnvilNotFound(anvil_path))?;
+
+        let port = match self.port {
+            Some(port) => port,
+            None => unused_port()?,
+        };
+        let mut command = Command::new(anvil_path);
+        command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .args(["--port".to_owned(), port.to_string()]);
+        if let Some(chain_id) = self.chain_id {
+            command.args(["--chain-id".to_owned(), chain_id.to_string()]);
+        }
+        if let Some(block_time) = self.block_time {
+            command.args(["--block-time".to_owned(), block_time.to_string()]);
+        }
+        if let Some(mnemonic) = self.mnemonic {
+            command.args(["--mnemonic".to_owned(), mnemonic]);
+        }
+        if let Some(accounts) = self.accounts {
+            command.args(["--accounts".to_owned(), accounts.to_string()]);
+        }
+        if let Some(load_state) = self.load_state {
+            command.arg("--load-state").arg(load_state);
+        }
+        if let Some(dump_state) = self.dump_state {
+            command.arg("--dump-state").arg(dump_state);
+        }
+        if let Some(gas_limit) = self.gas_limit {
+            command.args(["--gas-limit".to_owned(), gas_limit.to_string()]);
+        }
+
+        let chain_id = self.chain_id.unwrap_or(ANVIL_DEFAULT_CHAIN_ID);
+        let anvil = AnvilProcess::start(command, port, chain_id)?;
+
+        let provider = Provider::<Http>::try_from(anvil.endpoint.as_str())
+            .map_err(|_| Error::UrlParser)?
+            .interval(Duration::from_millis(POLLING_INTERVAL_MS));
+
+        let wallet = anvil
+            .wallets
+            .first()
+            .ok_or_else(|| Error::AnvilSpawn("no prefunded accounts".to_owned()))?
+            .clone();
+        let client = SignerMiddleware::new(provider.clone(), wallet.with_chain_id(anvil.chain_id));
+
+        Ok(EthereumClient::new(Some(anvil), client))
+    }
+}
+
+/// Running Anvil process, killed when dropped
+pub(crate) struct AnvilProcess {
+    child: Child,
+    pub(crate) endpoint: String,
+    pub(crate) chain_id: u64,
+    /// Prefunded accounts, in the order listed by Anvil
+    pub(crate) wallets: Vec<LocalWallet>,
+    pub(crate) addresses: Vec<Address>,
+}
+
+impl AnvilProcess {
+    /// Spawns `command` and waits for Anvil to listen, collecting the prefunded
+    /// accounts it prints on startup
+    fn start(mut command: Command, port: u16, chain_id: u64) -> Result<Self, Error> {
+        let mut child = command
+            .spawn()
+            .map_err(|e| Error::AnvilSpawn(e.to_string()))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| Error::AnvilSpawn("stdout not captured".to_owned()))?;
+
+        // Lines are read on a separate thread so that a silent process can time out
+        let (lines_tx, lines) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if lines_tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut anvil = Self {
+            child,
+            endpoint: format!("http://127.0.0.1:{port}"),
+            chain_id,
+            wallets: Vec::new(),
+            addresses: Vec::new(),
+        };
+        let deadline = Instant::now() + STARTUP_TIMEOUT;
+        let mut private_keys_section = false;
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            let line = match lines.recv_timeout(timeout) {
+                Ok(line) => line,
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    return Err(Error::AnvilSpawn(
+                        "timed out waiting for Anvil to listen".to_owned(),
+                    ))
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    let status = anvil.child.wait().ok();
+                    return Err(Error::AnvilSpawn(format!(
+                        "Anvil exited before listening ({status:?})"
+                    )));
+                }
+            };
+
+            if line.contains("Listening on") {
+                return Ok(anvil);
+            }
+            if line.starts_with("Private Keys") {
+                private_keys_section = true;
+            } else if private_keys_section && line.starts_with('(') {
+                let key = line.split("0x").last().unwrap_or_default().trim();
+                let wallet: LocalWallet = key
+                    .parse()
+                    .map_err(|_| Error::AnvilSpawn(format!("invalid private key line `{line}`")))?;
+                anvil.addresses.push(wallet.address());
+                anvil.wallets.push(wallet);
+            }
+        }
+    }
+}
+
+impl Drop for AnvilProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Resolves a bare binary name (e.g. `anvil`) through `$PATH`, returns `None` if the
+/// binary does not exist
+fn resolve_binary(path: &Path) -> Option<PathBuf> {
+    if path.components().count() == 1 {
+        if let Some(binary) = std::env::var_os("PATH").and_then(|paths| {
+            std::env::split_paths(&paths)
+                .map(|dir| dir.join(path))
+                .find(|binary| binary.is_file())
+        }) {
+            return Some(binary);
+        }
+    }
+    path.is_file().then(|| path.to_owned())
+}
+
+fn unused_port() -> Result<u16, Error> {
+    TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .map(|address| address.port())
+        .map_err(|e| Error::AnvilSpawn(format!("no unused port: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_binary_through_path() {
+        let sh = resolve_binary(Path::new("sh")).unwrap();
+        assert!(sh.is_absolute() && sh.is_file());
+        assert_eq!(resolve_binary(&sh), Some(sh));
+        assert_eq!(resolve_binary(Path::new("no-such-anvil-binary")), None);
+    }
+}
@@ -0,0 +1,567 @@
+use ethers::abi::Abi;
+use ethers::types::{Address, Bytes, TransactionReceipt, H256};
+use ethers::utils::keccak256;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use utils::registry::DeploymentEntry;
+
+use crate::Error;
+
+/// Length of a library placeholder in the hex encoded bytecode (the size of an address)
+const PLACEHOLDER_LEN: usize = 40;
+
+/// Offsets of the library placeholders: source file -> library name -> `(start, length)` in bytes
+type LinkReferences = BTreeMap<String, BTreeMap<String, Vec<(usize, usize)>>>;
+
+/// Supported contract build artifacts formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtifactFormat {
+    /// `forge build` output: `{"abi": [..], "bytecode": {"object": "0x..", "linkReferences": {..}}, ..}`
+    Forge,
+    /// Hardhat artifacts: `{"abi": [..], "bytecode": "0x..", "linkReferences": {..}, ..}`
+    /// (also the per contract files extracted from solc output)
+    Hardhat,
+    /// `solc --combined-json abi,bin` output: `{"contracts": {"path:Name": {"abi": .., "bin": ".."}}}`
+    SolcCombinedJson,
+}
+
+impl ArtifactFormat {
+    pub fn detect(artifacts: &Value) -> Result<Self, Error> {
+        if artifacts.get("contracts").is_some() {
+            return Ok(Self::SolcCombinedJson);
+        }
+        match artifacts.get("bytecode") {
+            Some(Value::Object(_)) => Ok(Self::Forge),
+            Some(Value::String(_)) => Ok(Self::Hardhat),
+            Some(_) => Err(Error::BytecodeObject),
+            None => Err(Error::ContractBuildArtifacts("bytecode")),
+        }
+    }
+}
+
+/// ABI and bytecode of a contract, with its library placeholders left to be linked
+/// at deploy time (see [`crate::DeployOptions::library`]).
+#[derive(Debug, Clone)]
+pub struct ContractArtifact {
+    name: Option<String>,
+    abi: Abi,
+    bytecode: String,
+    link_references: LinkReferences,
+    deployed_bytecode: Option<String>,
+    deployed_link_references: LinkReferences,
+}
+
+impl ContractArtifact {
+    /// Parses build artifacts (JSON file contents) of any of the [`ArtifactFormat`]s.
+    /// solc combined-json output must contain a single contract, use
+    /// [`Self::parse_combined_json`] to pick one otherwise.
+    pub fn parse(contract_build_artifacts: &str) -> Result<Self, Error> {
+        Self::from_value(serde_json::from_str(contract_build_artifacts)?)
+    }
+
+    /// Same as [`Self::parse`] with the JSON file contents as bytes
+    pub fn parse_slice(contract_build_artifacts: &[u8]) -> Result<Self, Error> {
+        Self::from_value(serde_json::from_slice(contract_build_artifacts)?)
+    }
+
+    fn from_value(artifacts: Value) -> Result<Self, Error> {
+        match ArtifactFormat::detect(&artifacts)? {
+            ArtifactFormat::Forge => Self::from_forge(artifacts),
+            ArtifactFormat::Hardhat => Self::from_hardhat(artifacts),
+            ArtifactFormat::SolcCombinedJson => Self::from_combined_json(artifacts, None),
+        }
+    }
+
+    /// Parses the contract named `contract` (`Name` or `path:Name`) of solc combined-json output
+    pub fn parse_combined_json(
+        contract_build_artifacts: &str,
+        contract: &str,
+    ) -> Result<Self, Error> {
+        let artifacts: Value = serde_json::from_str(contract_build_artifacts)?;
+        Self::from_combined_json(artifacts, Some(contract))
+    }
+
+    /// Name the deployments are recorded under in the [`utils::registry::DeploymentRegistry`],
+    /// taken from the artifacts if they include it (not the case of forge output).
+    /// Overridden by [`crate::DeployOptions::registry_name`].
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn abi(&self) -> &Abi {
+        &self.abi
+    }
+
+    /// Hash of the creation bytecode before library linking: keccak256 of its lowercase hex
+    /// text without `0x` prefix, the library placeholders making unlinked bytecode invalid hex.
+    pub fn hash(&self) -> H256 {
+        H256(keccak256(self.bytecode.to_lowercase()))
+    }
+
+    /// Registry entry named `name` of a contract deployed at `address` from these artifacts
+    pub fn deployment_entry(
+        &self,
+        name: impl Into<String>,
+        address: Address,
+        receipt: Option<&TransactionReceipt>,
+    ) -> DeploymentEntry {
+        DeploymentEntry {
+            name: name.into(),
+            address,
+            implementation: address,
+            proxy_version: None,
+            artifact_hash: self.hash(),
+            tx_hash: receipt.map(|receipt| receipt.transaction_hash),
+            block_number: receipt
+                .and_then(|receipt| receipt.block_number)
+                .map(|block_number| block_number.as_u64()),
+        }
+    }
+
+    /// Fully qualified names (`path:Name`) of the libraries to link
+    pub fn libraries(&self) -> Vec<String> {
+        self.link_references
+            .iter()
+            .chain(&self.deployed_link_references)
+            .flat_map(|(file, names)| names.keys().map(move |name| format!("{file}:{name}")))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Creation bytecode with the library addresses linked,
+    /// keyed by `Name` or `path:Name`
+    pub fn bytecode(&self, libraries: &BTreeMap<String, Address>) -> Result<Bytes, Error> {
+        link(&self.bytecode, &self.link_references, libraries)
+    }
+
+    /// Runtime bytecode with the library addresses linked,
+    /// keyed by `Name` or `path:Name`
+    pub fn deployed_bytecode(&self, libraries: &BTreeMap<String, Address>) -> Result<Bytes, Error> {
+        let deployed_bytecode = self
+            .deployed_bytecode
+            .as_deref()
+            .ok_or(Error::ContractBuildArtifacts("deployedBytecode"))?;
+        link(deployed_bytecode, &self.deployed_link_references, libraries)
+    }
+
+    fn from_forge(mut artifacts: Value) -> Result<Self, Error> {
+        let bytecode = artifacts
+            .get("bytecode")
+            .ok_or(Error::ContractBuildArtifacts("bytecode"))?;
+        let object = bytecode
+            .get("object")
+            .ok_or(Error::ContractBuildArtifacts("bytecode.object"))?
+            .as_str()
+            .ok_or(Error::BytecodeObject)?;
+        let link_references = parse_link_references(bytecode.get("linkReferences"))?;
+
+        let deployed = artifacts.get("deployedBytecode");
+        let deployed_bytecode = deployed
+            .and_then(|deployed| deployed.get("object"))
+            .and_then(Value::as_str)
+            .map(strip_hex_prefix);
+        let deployed_link_references =
+            parse_link_references(deployed.and_then(|deployed| deployed.get("linkReferences")))?;
+
+        Ok(Self {
+            name: None,
+            bytecode: strip_hex_prefix(object),
+            link_references,
+            deployed_bytecode,
+            deployed_link_references,
+            abi: parse_abi(artifacts.get_mut("abi"))?,
+        })
+    }
+
+    fn from_hardhat(mut artifacts: Value) -> Result<Self, Error> {
+        let bytecode = artifacts
+            .get("bytecode")
+            .and_then(Value::as_str)
+            .ok_or(Error::BytecodeObject)?;
+
+        Ok(Self {
+            name: artifacts
+                .get("contractName")
+                .and_then(Value::as_str)
+                .map(str::to_owned),
+            bytecode: strip_hex_prefix(bytecode),
+            link_references: parse_link_references(artifacts.get("linkReferences"))?,
+            deployed_bytecode: artifacts
+                .get("deployedBytecode")
+                .and_then(Value::as_str)
+                .map(strip_hex_prefix),
+            deployed_link_references: parse_link_references(
+                artifacts.get("deployedLinkReferences"),
+            )?,
+            abi: parse_abi(artifacts.get_mut("abi"))?,
+        })
+    }
+
+    /// solc combined-json output has no link references, the placeholders are
+    /// resolved against the other contracts of the output instead.
+    fn from_combined_json(mut artifacts: Value, contract: Option<&str>) -> Result<Self, Error> {
+        let contracts = artifacts
+            .get_mut("contracts")
+            .and_then(Value::as_object_mut)
+            .ok_or(Error::ContractBuildArtifacts("contracts"))?;
+
+        let matching: Vec<String> = contracts
+            .keys()
+            .filter(|key| match contract {
+                Some(contract) => {
+                    key.as_str() == contract || key.rsplit(':').next() == Some(contract)
+                }
+                None => true,
+            })
+            .cloned()
+            .collect();
+        let key = match matching.as_slice() {
+            [key] => key.clone(),
+            [] => {
+                return Err(Error::ArtifactContractNotFound(
+                    contract.unwrap_or_default().to_owned(),
+                ))
+            }
+            _ => {
+                return Err(Error::AmbiguousArtifactContract(
+                    contract.unwrap_or("*").to_owned(),
+                ))
+            }
+        };
+
+        let qualified_names: Vec<String> = contracts.keys().cloned().collect();
+        let entry = contracts
+            .get_mut(&key)
+            .ok_or(Error::ContractBuildArtifacts("contracts"))?;
+        let bin = entry
+            .get("bin")
+            .ok_or(Error::ContractBuildArtifacts("bin"))?
+            .as_str()
+            .ok_or(Error::BytecodeObject)?;
+        let bytecode = strip_hex_prefix(bin);
+        let link_references = find_placeholders(&bytecode, &qualified_names);
+
+        // Older solc versions output the ABI as a JSON string
+        let abi = match entry.get_mut("abi") {
+            Some(Value::String(abi)) => serde_json::from_str(abi)?,
+            abi => parse_abi(abi)?,
+        };
+
+        Ok(Self {
+            name: key.rsplit(':').next().map(str::to_owned),
+            abi,
+            bytecode,
+            link_references,
+            deployed_bytecode: None,
+            deployed_link_references: LinkReferences::new(),
+        })
+    }
+}
+
+/// Declares a [`BundledArtifact`] constant for a file of the `artifacts` directory of this repository,
+/// embedded when the invoking crate is built with its `embedded-artifacts` feature.
+#[macro_export]
+macro_rules! bundled_artifact {
+    ($vis:vis $name:ident, $path:literal) => {
+        #[cfg(feature = "embedded-artifacts")]
+        $vis const $name: $crate::BundledArtifact = $crate::BundledArtifact {
+            path: $path,
+            contents: Some(include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/../../../artifacts/",
+                $path
+            ))),
+        };
+        #[cfg(not(feature = "embedded-artifacts"))]
+        $vis const $name: $crate::BundledArtifact = $crate::BundledArtifact {
+            path: $path,
+            contents: None,
+        };
+    };
+}
+
+/// Build artifacts of a contract deployed by the deployment helpers (proxies, StarkWare contracts)
+#[derive(Debug, Clone, Copy)]
+pub struct BundledArtifact {
+    /// Path relative to the artifacts directory, e.g. `starkgate-contracts/StarkgateManager.json`
+    pub path: &'static str,
+    /// JSON file contents, if embedded at compile time
+    pub contents: Option<&'static str>,
+}
+
+/// Where the deployment helpers load their [`BundledArtifact`]s from.
+/// Artifacts supplied with [`Self::artifact`], otherwise the directory if set, otherwise the
+/// artifacts embedded at compile time (`embedded-artifacts` feature, enabled by default).
+/// An artifact missing from the directory is an error, the embedded one is not used instead.
+#[derive(Debug, Clone, Default)]
+pub struct ArtifactSource {
+    directory: Option<PathBuf>,
+    artifacts: BTreeMap<String, Vec<u8>>,
+}
+
+impl ArtifactSource {
+    /// Artifacts embedded at compile time only
+    pub fn embedded() -> Self {
+        Self::default()
+    }
+
+    /// Artifacts read at deploy time from a directory laid out as the `artifacts` directory
+    /// of this repository (e.g. `<directory>/starkgate-contracts/StarkgateManager.json`)
+    pub fn directory(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: Some(directory.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Supplies the JSON file contents of the artifact at `path` (relative to the artifacts directory)
+    pub fn artifact(mut self, path: impl Into<String>, contents: impl Into<Vec<u8>>) -> Self {
+        self.artifacts.insert(path.into(), contents.into());
+        self
+    }
+
+    /// Loads a bundled artifact, named after its file (e.g. `StarkgateManager`)
+    pub fn load(&self, artifact: &BundledArtifact) -> Result<ContractArtifact, Error> {
+        let contract_artifact = if let Some(contents) = self.artifacts.get(artifact.path) {
+            ContractArtifact::parse_slice(contents)?
+        } else if let Some(directory) = &self.directory {
+            let path = directory.join(artifact.path);
+            let contents = std::fs::read(&path).map_err(|e| Error::ArtifactRead(path, e))?;
+            ContractArtifact::parse_slice(&contents)?
+        } else {
+            let contents = artifact
+                .contents
+                .ok_or(Error::ArtifactNotEmbedded(artifact.path))?;
+            ContractArtifact::parse(contents)?
+        };
+
+        match Path::new(artifact.path).file_stem() {
+            Some(name) => Ok(contract_artifact.with_name(name.to_string_lossy())),
+            None => Ok(contract_artifact),
+        }
+    }
+}
+
+fn parse_abi(abi: Option<&mut Value>) -> Result<Abi, Error> {
+    let abi = abi.ok_or(Error::ContractBuildArtifacts("abi"))?.take();
+    Ok(serde_json::from_value(abi)?)
+}
+
+fn strip_hex_prefix(code: &str) -> String {
+    code.trim_start_matches("0x").to_owned()
+}
+
+fn parse_link_references(link_references: Option<&Value>) -> Result<LinkReferences, Error> {
+    let Some(files) = link_references.and_then(Value::as_object) else {
+        return Ok(LinkReferences::new());
+    };
+
+    let mut parsed = LinkReferences::new();
+    for (file, names) in files {
+        let names = names
+            .as_object()
+            .ok_or(Error::ContractBuildArtifacts("linkReferences"))?;
+        for (name, offsets) in names {
+            let offsets = offsets
+                .as_array()
+                .ok_or(Error::ContractBuildArtifacts("linkReferences"))?
+                .iter()
+                .map(|offset| {
+                    let field = |field| offset.get(field).and_then(Value::as_u64);
+                    match (field("start"), field("length")) {
+                        (Some(start), Some(length)) => Ok((start as usize, length as usize)),
+                        _ => Err(Error::ContractBuildArtifacts("linkReferences.start")),
+                    }
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            parsed
+                .entry(file.clone())
+                .or_default()
+                .insert(name.clone(), offsets);
+        }
+    }
+    Ok(parsed)
+}
+
+/// Link references of the placeholders left by solc for the libraries among `qualified_names`:
+/// `__$<34 hex chars of the keccak256 of path:Name>$__` (solc >= 0.5) or
+/// `__<path:Name, truncated to 36 chars>__` padded with underscores (older versions).
+fn find_placeholders(code: &str, qualified_names: &[String]) -> LinkReferences {
+    let mut link_references = LinkReferences::new();
+    for qualified_name in qualified_names {
+        let hash = hex::encode(keccak256(qualified_name.as_bytes()));
+        let placeholders = [
+            format!("__${}$__", &hash[..34]),
+            format!("{:_<40}", format!("__{:.36}", qualified_name)),
+        ];
+
+        let offsets: Vec<(usize, usize)> = placeholders
+            .iter()
+            .flat_map(|placeholder| code.match_indices(placeholder.as_str()))
+            .filter(|(index, _)| index % 2 == 0)
+            .map(|(index, _)| (index / 2, PLACEHOLDER_LEN / 2))
+            .collect();
+        if offsets.is_empty() {
+            continue;
+        }
+
+        let (file, name) = qualified_name
+            .rsplit_once(':')
+            .unwrap_or(("", qualified_name.as_str()));
+        link_references
+            .entry(file.to_owned())
+            .or_default()
+            .insert(name.to_owned(), offsets);
+    }
+    link_references
+}
+
+/// Replaces the library placeholders of hex encoded bytecode with the library addresses
+fn link(
+    code: &str,
+    link_references: &LinkReferences,
+    libraries: &BTreeMap<String, Address>,
+) -> Result<Bytes, Error> {
+    let mut code = code.to_owned();
+    for (file, names) in link_references {
+        for (name, offsets) in names {
+            let qualified_name = format!("{file}:{name}");
+            let address = libraries
+                .get(&qualified_name)
+                .or_else(|| libraries.get(name))
+                .ok_or_else(|| Error::UnlinkedLibrary(qualified_name.clone()))?;
+            let address = hex::encode(address);
+
+            for &(start, length) in offsets {
+                let range = start * 2..(start + length) * 2;
+                if length * 2 != PLACEHOLDER_LEN || range.end > code.len() {
+                    return Err(Error::InvalidLinkReference(qualified_name));
+                }
+                code.replace_range(range, &address);
+            }
+        }
+    }
+
+    // Placeholders without link references, e.g. libraries of another compilation unit
+    if let Some(index) = code.find("__") {
+        let end = (index + PLACEHOLDER_LEN).min(code.len());
+        return Err(Error::UnlinkedLibrary(code[index..end].to_owned()));
+    }
+
+    Ok(Bytes::from(hex::decode(code)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_combined_json_library() {
+        let placeholder = format!("__${}$__", &hex::encode(keccak256("src/Lib.sol:Lib"))[..34]);
+        let artifacts = serde_json::json!({
+            "contracts": {
+                "src/Lib.sol:Lib": { "abi": [], "bin": "6000" },
+                "src/Main.sol:Main": { "abi": "[]", "bin": format!("73{placeholder}00") },
+            }
+        })
+        .to_string();
+
+        assert!(matches!(
+            ContractArtifact::parse(&artifacts),
+            Err(Error::AmbiguousArtifactContract(_))
+        ));
+
+        let main = ContractArtifact::parse_combined_json(&artifacts, "Main").unwrap();
+        assert_eq!(main.libraries(), vec!["src/Lib.sol:Lib".to_owned()]);
+        assert!(matches!(
+            main.bytecode(&BTreeMap::new()),
+            Err(Error::UnlinkedLibrary(_))
+        ));
+
+        let library = Address::repeat_byte(0x11);
+        let libraries = BTreeMap::from([("Lib".to_owned(), library)]);
+        let bytecode = main.bytecode(&libraries).unwrap();
+        assert_eq!(bytecode.len(), 22);
+        assert_eq!(&bytecode[1..21], library.as_bytes());
+    }
+
+    #[test]
+    fn test_link_forge_library() {
+        let placeholder = format!("__${}$__", &hex::encode(keccak256("src/Lib.sol:Lib"))[..34]);
+        let link_references = serde_json::json!({
+            "src/Lib.sol": { "Lib": [{ "start": 1, "length": 20 }] }
+        });
+        let artifacts = serde_json::json!({
+            "abi": [],
+            "bytecode": {
+                "object": format!("0x73{placeholder}00"),
+                "linkReferences": link_references,
+            },
+            "deployedBytecode": {
+                "object": format!("0x600073{placeholder}"),
+                "linkReferences": { "src/Lib.sol": { "Lib": [{ "start": 3, "length": 20 }] } },
+            },
+        })
+        .to_string();
+
+        let main = ContractArtifact::parse(&artifacts).unwrap();
+        assert_eq!(main.name(), None);
+        assert_eq!(main.libraries(), vec!["src/Lib.sol:Lib".to_owned()]);
+        assert!(matches!(
+            main.bytecode(&BTreeMap::new()),
+            Err(Error::UnlinkedLibrary(_))
+        ));
+
+        let library = Address::repeat_byte(0x22);
+        let libraries = BTreeMap::from([("src/Lib.sol:Lib".to_owned(), library)]);
+        let bytecode = main.bytecode(&libraries).unwrap();
+        assert_eq!(bytecode.len(), 22);
+        assert_eq!(&bytecode[1..21], library.as_bytes());
+        let deployed_bytecode = main.deployed_bytecode(&libraries).unwrap();
+        assert_eq!(deployed_bytecode.len(), 23);
+        assert_eq!(&deployed_bytecode[3..], library.as_bytes());
+    }
+
+    #[test]
+    fn test_parse_hardhat_artifact() {
+        let placeholder = format!("__${}$__", &hex::encode(keccak256("src/Lib.sol:Lib"))[..34]);
+        let artifacts = serde_json::json!({
+            "_format": "hh-sol-artifact-1",
+            "contractName": "Main",
+            "sourceName": "src/Main.sol",
+            "abi": [{
+                "type": "function",
+                "name": "value",
+                "inputs": [],
+                "outputs": [{ "name": "", "type": "uint256" }],
+                "stateMutability": "view",
+            }],
+            "bytecode": format!("0x73{placeholder}00"),
+            "deployedBytecode": "0x6000",
+            "linkReferences": { "src/Lib.sol": { "Lib": [{ "start": 1, "length": 20 }] } },
+            "deployedLinkReferences": {},
+        })
+        .to_string();
+
+        let main = ContractArtifact::parse(&artifacts).unwrap();
+        assert_eq!(main.name(), Some("Main"));
+        assert!(main.abi().function("value").is_ok());
+        assert_eq!(main.libraries(), vec!["src/Lib.sol:Lib".to_owned()]);
+
+        let library = Address::repeat_byte(0x33);
+        let libraries = BTreeMap::from([("Lib".to_owned(), library)]);
+        let bytecode = main.bytecode(&libraries).unwrap();
+        assert_eq!(&bytecode[1..21], library.as_bytes());
+        assert_eq!(
+            main.deployed_bytecode(&libraries).unwrap(),
+            Bytes::from(vec![0x60, 0x00])
+        );
+    }
+}
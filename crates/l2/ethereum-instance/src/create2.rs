@@ -0,0 +1,369 @@
+use ethers::abi::{Abi, Tokenize};
+use ethers::contract::{ContractError, ContractInstance};
+use ethers::providers::Middleware;
+use ethers::types::{Address, Bytes, TransactionReceipt, TransactionRequest, H256, U256};
+use ethers::utils::{get_create2_address, keccak256};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::{
+    send_transaction_with_options, ContractArtifact, DeployOptions, Error,
+    LocalWalletSignerMiddleware,
+};
+
+/// Address of the deterministic deployment proxy (<https://github.com/Arachnid/deterministic-deployment-proxy>).
+/// The same on every chain as it is deployed with a pre-signed transaction that is not chain specific.
+pub const CREATE2_FACTORY_ADDRESS: &str = "0x4e59b44847b379578588920ca78fbf26c0b4956c";
+/// Signer of the factory deployment transaction, must be funded to pay for it
+const CREATE2_FACTORY_DEPLOYER: &str = "0x3fab184622dc19b6109349b94811493bf2a45362";
+/// Gas limit (100000) times gas price (100 gwei) of the factory deployment transaction
+const CREATE2_FACTORY_DEPLOYMENT_COST: u64 = 10_000_000_000_000_000;
+const CREATE2_FACTORY_DEPLOYMENT_TX: &str = "0xf8a58085174876e800830186a08080b853604580600e600039806000f350fe7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe03601600081602082378035828234f58015156039578182fd5b8082525050506014600cf31ba02222222222222222222222222222222222222222222222222222222222222222a02222222222222222222222222222222222222222222222222222222222222222";
+
+/// Init code of the CREATE2 call factory, itself deployed through the CREATE2 factory.
+/// It deploys a contract with CREATE2 then calls it in the same transaction, so that a contract
+/// granting roles to its deployer (the safe proxies) can hand them over. Calldata is
+/// `salt ‖ init code length ‖ init code` followed by `call length ‖ call data` for each call,
+/// the CREATE2 salt being `keccak256(sender ‖ salt)` so that only the sender can deploy at the
+/// predicted address. Returns the address of the contract, reverts with the revert data of a
+/// failing call.
+///
+/// ```text
+/// CALLER PUSH1 0 MSTORE PUSH1 0 CALLDATALOAD PUSH1 32 MSTORE PUSH1 64 PUSH1 0 SHA3
+/// PUSH1 32 CALLDATALOAD DUP1 PUSH1 64 PUSH1 0 CALLDATACOPY
+/// DUP2 DUP2 PUSH1 0 PUSH1 0 CREATE2 DUP1 ISZERO PUSH2 @bubble JUMPI SWAP1 PUSH1 64 ADD
+/// loop: JUMPDEST CALLDATASIZE DUP2 LT ISZERO PUSH2 @done JUMPI
+///     DUP1 CALLDATALOAD DUP1 DUP3 PUSH1 32 ADD PUSH1 0 CALLDATACOPY
+///     PUSH1 0 PUSH1 0 DUP3 PUSH1 0 PUSH1 0 DUP8 GAS CALL ISZERO PUSH2 @bubble JUMPI
+///     ADD PUSH1 32 ADD PUSH2 @loop JUMP
+/// done: JUMPDEST POP PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+/// bubble: JUMPDEST RETURNDATASIZE PUSH1 0 PUSH1 0 RETURNDATACOPY RETURNDATASIZE PUSH1 0 REVERT
+/// ```
+const CREATE2_CALL_FACTORY_INIT_CODE: &str = "0x606a80600b6000396000f3336000526000356020526040600020602035806040600037818160006000f5801561005f57906040015b368110156100555780358082602001600037600060008260006000875af11561005f5701602001610029565b5060005260206000f35b3d600060003e3d6000fd";
+
+fn factory_address() -> Address {
+    CREATE2_FACTORY_ADDRESS
+        .parse()
+        .expect("valid factory address")
+}
+
+fn call_factory_init_code() -> Bytes {
+    hex::decode(CREATE2_CALL_FACTORY_INIT_CODE.trim_start_matches("0x"))
+        .expect("valid call factory init code")
+        .into()
+}
+
+/// Address of the CREATE2 call factory (see [`deploy_contract_artifact_create2_call_with_receipt`]),
+/// deployed through the CREATE2 factory with a zero salt
+pub fn create2_call_factory_address() -> Address {
+    get_create2_address(factory_address(), [0u8; 32], call_factory_init_code())
+}
+
+fn create2_call_address(deployer: Address, salt: H256, init_code: Bytes) -> Address {
+    let salt = keccak256([H256::from(deployer).as_bytes(), salt.as_bytes()].concat());
+    get_create2_address(create2_call_factory_address(), salt, init_code)
+}
+
+/// Computes the address a contract will be deployed at by [`deploy_contract_create2`]
+/// given the same build artifacts, constructor args and salt.
+/// Contracts linked to libraries are not supported.
+pub fn predict_create2_address<T: Tokenize>(
+    contract_build_artifacts: &str,
+    constructor_args: T,
+    salt: H256,
+) -> Result<Address, Error> {
+    let artifact = ContractArtifact::parse(contract_build_artifacts)?;
+    let bytecode = artifact.bytecode(&BTreeMap::new())?;
+    let init_code = encode_init_code(artifact.abi(), bytecode, constructor_args)?;
+
+    Ok(get_create2_address(
+        factory_address(),
+        salt.as_bytes(),
+        init_code,
+    ))
+}
+
+/// Computes the address a contract will be deployed at by
+/// [`deploy_contract_artifact_create2_call_with_receipt`] when sent by `deployer`,
+/// given the same build artifacts, constructor args and salt.
+/// Contracts linked to libraries are not supported.
+pub fn predict_create2_call_address<T: Tokenize>(
+    deployer: Address,
+    contract_build_artifacts: &str,
+    constructor_args: T,
+    salt: H256,
+) -> Result<Address, Error> {
+    let artifact = ContractArtifact::parse(contract_build_artifacts)?;
+    let bytecode = artifact.bytecode(&BTreeMap::new())?;
+    let init_code = encode_init_code(artifact.abi(), bytecode, constructor_args)?;
+
+    Ok(create2_call_address(deployer, salt, init_code))
+}
+
+/// Deploys the CREATE2 factory unless it is already present on chain.
+/// The deployer account is funded from the client account if needed.
+pub async fn ensure_create2_factory(client: Arc<LocalWalletSignerMiddleware>) -> Result<(), Error> {
+    if !client
+        .provider()
+        .get_code(factory_address(), None)
+        .await?
+        .is_empty()
+    {
+        return Ok(());
+    }
+
+    let deployer: Address = CREATE2_FACTORY_DEPLOYER
+        .parse()
+        .expect("valid deployer address");
+    let cost = U256::from(CREATE2_FACTORY_DEPLOYMENT_COST);
+    let balance = client.provider().get_balance(deployer, None).await?;
+    if balance < cost {
+        let funding = TransactionRequest::new().to(deployer).value(cost - balance);
+        let pending = client
+            .send_transaction(funding, None)
+            .await
+            .map_err(ContractError::<LocalWalletSignerMiddleware>::from_middleware_error)?;
+        let tx_hash = pending.tx_hash();
+        pending.await?.ok_or(Error::Dropped(tx_hash))?;
+    }
+
+    let deployment_tx = Bytes::from(hex::decode(
+        CREATE2_FACTORY_DEPLOYMENT_TX.trim_start_matches("0x"),
+    )?);
+    let pending = client
+        .send_raw_transaction(deployment_tx)
+        .await
+        .map_err(ContractError::<LocalWalletSignerMiddleware>::from_middleware_error)?;
+    let tx_hash = pending.tx_hash();
+    pending.await?.ok_or(Error::Dropped(tx_hash))?;
+
+    log::debug!("ℹ️  CREATE2 factory deployed : {:?}", factory_address());
+    Ok(())
+}
+
+/// Deploys new smart contract at a deterministic address through the CREATE2 factory using:
+///     - Build artifacts (JSON file contents, see [`ContractArtifact::parse`])
+///     - Constructor args (use () if no args expected)
+///     - Salt
+///     - Deployment options
+/// The address only depends on the artifacts, the constructor args and the salt
+/// (see [`predict_create2_address`]). If a contract is already deployed at this address
+/// it is returned as is.
+pub async fn deploy_contract_create2<T: Tokenize>(
+    client: Arc<LocalWalletSignerMiddleware>,
+    contract_build_artifacts: &str,
+    constructor_args: T,
+    salt: H256,
+    options: &DeployOptions,
+) -> Result<ContractInstance<Arc<LocalWalletSignerMiddleware>, LocalWalletSignerMiddleware>, Error>
+{
+    let artifact = ContractArtifact::parse(contract_build_artifacts)?;
+    deploy_contract_artifact_create2(client, &artifact, constructor_args, salt, options).await
+}
+
+/// Same as [`deploy_contract_create2`] with already parsed build artifacts,
+/// the contract is recorded in the registry of the options if it is named (see
+/// [`DeployOptions::deployment_entry`]).
+pub async fn deploy_contract_artifact_create2<T: Tokenize>(
+    client: Arc<LocalWalletSignerMiddleware>,
+    artifact: &ContractArtifact,
+    constructor_args: T,
+    salt: H256,
+    options: &DeployOptions,
+) -> Result<ContractInstance<Arc<LocalWalletSignerMiddleware>, LocalWalletSignerMiddleware>, Error>
+{
+    let (contract, receipt) = deploy_contract_artifact_create2_with_receipt(
+        client,
+        artifact,
+        constructor_args,
+        salt,
+        options,
+    )
+    .await?;
+
+    if let Some(entry) = options.deployment_entry(artifact, contract.address(), receipt.as_ref()) {
+        options.record(entry)?;
+    }
+    Ok(contract)
+}
+
+/// Same as [`deploy_contract_artifact_create2`] returning the deployment receipt
+/// (`None` if the contract was already deployed), the contract is not recorded in the registry.
+pub async fn deploy_contract_artifact_create2_with_receipt<T: Tokenize>(
+    client: Arc<LocalWalletSignerMiddleware>,
+    artifact: &ContractArtifact,
+    constructor_args: T,
+    salt: H256,
+    options: &DeployOptions,
+) -> Result<
+    (
+        ContractInstance<Arc<LocalWalletSignerMiddleware>, LocalWalletSignerMiddleware>,
+        Option<TransactionReceipt>,
+    ),
+    Error,
+> {
+    let bytecode = artifact.bytecode(&options.libraries)?;
+    let init_code = encode_init_code(artifact.abi(), bytecode, constructor_args)?;
+    let abi = artifact.abi().clone();
+    let address = get_create2_address(factory_address(), salt.as_bytes(), init_code.clone());
+
+    if !client.provider().get_code(address, None).await?.is_empty() {
+        log::debug!("ℹ️  Contract already deployed : {:?}", address);
+        return Ok((ContractInstance::new(address, abi, client), None));
+    }
+
+    ensure_create2_factory(client.clone()).await?;
+
+    // The factory expects the salt followed by the init code
+    let calldata = [salt.as_bytes(), init_code.as_ref()].concat();
+    let tx = TransactionRequest::new()
+        .to(factory_address())
+        .data(calldata);
+    let receipt = send_transaction_with_options(&client, tx.into(), options).await?;
+
+    Ok((ContractInstance::new(address, abi, client), Some(receipt)))
+}
+
+/// Deploys the CREATE2 call factory (and the CREATE2 factory) unless it is already present on chain.
+pub async fn ensure_create2_call_factory(
+    client: Arc<LocalWalletSignerMiddleware>,
+) -> Result<(), Error> {
+    let address = create2_call_factory_address();
+    if !client.provider().get_code(address, None).await?.is_empty() {
+        return Ok(());
+    }
+
+    ensure_create2_factory(client.clone()).await?;
+
+    let calldata = [[0u8; 32].as_slice(), call_factory_init_code().as_ref()].concat();
+    let tx = TransactionRequest::new()
+        .to(factory_address())
+        .data(calldata);
+    let pending = client
+        .send_transaction(tx, None)
+        .await
+        .map_err(ContractError::<LocalWalletSignerMiddleware>::from_middleware_error)?;
+    let tx_hash = pending.tx_hash();
+    pending.await?.ok_or(Error::Dropped(tx_hash))?;
+
+    log::debug!("ℹ️  CREATE2 call factory deployed : {:?}", address);
+    Ok(())
+}
+
+/// Same as [`deploy_contract_artifact_create2_with_receipt`] through the CREATE2 call factory:
+/// `calls` (ABI encoded calldata) are sent to the contract by the factory in the deployment
+/// transaction, the whole transaction reverting if one of them fails. The factory is the
+/// `msg.sender` of the constructor and of the calls, and the address depends on the sender of the
+/// transaction (see [`predict_create2_call_address`]). If a contract is already deployed at this
+/// address it is returned as is and the calls are not sent.
+pub async fn deploy_contract_artifact_create2_call_with_receipt<T: Tokenize>(
+    client: Arc<LocalWalletSignerMiddleware>,
+    artifact: &ContractArtifact,
+    constructor_args: T,
+    salt: H256,
+    calls: &[Bytes],
+    options: &DeployOptions,
+) -> Result<
+    (
+        ContractInstance<Arc<LocalWalletSignerMiddleware>, LocalWalletSignerMiddleware>,
+        Option<TransactionReceipt>,
+    ),
+    Error,
+> {
+    let bytecode = artifact.bytecode(&options.libraries)?;
+    let init_code = encode_init_code(artifact.abi(), bytecode, constructor_args)?;
+    let abi = artifact.abi().clone();
+    let address = create2_call_address(client.address(), salt, init_code.clone());
+
+    if !client.provider().get_code(address, None).await?.is_empty() {
+        log::debug!("ℹ️  Contract already deployed : {:?}", address);
+        return Ok((ContractInstance::new(address, abi, client), None));
+    }
+
+    ensure_create2_call_factory(client.clone()).await?;
+
+    let mut calldata = salt.as_bytes().to_vec();
+    for data in std::iter::once(&init_code).chain(calls) {
+        calldata.extend_from_slice(H256::from_low_u64_be(data.len() as u64).as_bytes());
+        calldata.extend_from_slice(data);
+    }
+    let tx = TransactionRequest::new()
+        .to(create2_call_factory_address())
+        .data(calldata);
+    let receipt = send_transaction_with_options(&client, tx.into(), options).await?;
+
+    Ok((ContractInstance::new(address, abi, client), Some(receipt)))
+}
+
+fn encode_init_code<T: Tokenize>(
+    abi: &Abi,
+    bytecode: Bytes,
+    constructor_args: T,
+) -> Result<Bytes, Error> {
+    let params = constructor_args.into_tokens();
+    match (abi.constructor(), params.is_empty()) {
+        (None, false) => Err(Error::UnexpectedConstructorArgs),
+        (None, true) => Ok(bytecode),
+        (Some(constructor), _) => Ok(constructor.encode_input(bytecode.to_vec(), &params)?.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AnvilBuilder;
+
+    /// Contract returning 42 to any call, its constructor taking a `uint256`
+    const ARTIFACTS: &str = r#"{
+        "abi": [{ "type": "constructor", "inputs": [{ "name": "value", "type": "uint256" }] }],
+        "bytecode": { "object": "0x600a600c600039600a6000f3602a60005260206000f3" }
+    }"#;
+
+    #[tokio::test]
+    #[ignore = "requires anvil"]
+    async fn test_create2_addresses_match_predictions() {
+        let ethereum = AnvilBuilder::new().spawn().unwrap();
+        let client = ethereum.signer();
+        // Anvil mines a block per transaction, only wait for inclusion
+        let options = DeployOptions::default().confirmations(1);
+        let salt = H256::repeat_byte(0x01);
+        let value = U256::from(7);
+
+        let predicted = predict_create2_address(ARTIFACTS, value, salt).unwrap();
+        let contract = deploy_contract_create2(client.clone(), ARTIFACTS, value, salt, &options)
+            .await
+            .unwrap();
+        assert_eq!(contract.address(), predicted);
+        assert!(!client.get_code(predicted, None).await.unwrap().is_empty());
+
+        let predicted =
+            predict_create2_call_address(client.address(), ARTIFACTS, value, salt).unwrap();
+        let artifact = ContractArtifact::parse(ARTIFACTS).unwrap();
+        let (contract, receipt) = deploy_contract_artifact_create2_call_with_receipt(
+            client.clone(),
+            &artifact,
+            value,
+            salt,
+            &[Bytes::from(vec![0x01])],
+            &options,
+        )
+        .await
+        .unwrap();
+        assert_eq!(contract.address(), predicted);
+        assert!(receipt.is_some());
+
+        // Deploying again returns the existing contract
+        let (contract, receipt) = deploy_contract_artifact_create2_call_with_receipt(
+            client,
+            &artifact,
+            value,
+            salt,
+            &[],
+            &options,
+        )
+        .await
+        .unwrap();
+        assert_eq!(contract.address(), predicted);
+        assert!(receipt.is_none());
+    }
+}
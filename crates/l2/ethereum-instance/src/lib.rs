@@ -1,52 +1,70 @@
 use ethers::abi::Tokenize;
-use ethers::contract::{ContractError, ContractFactory, ContractInstance};
+use ethers::contract::{ContractError, ContractFactory, ContractInstance, MULTICALL_ADDRESS};
 use ethers::prelude::SignerMiddleware;
-use ethers::providers::{Http, Provider, ProviderError};
-use ethers::signers::{LocalWallet, Signer};
-use ethers::types::Bytes;
-use ethers::utils::{Anvil, AnvilInstance};
-use hex::FromHex;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::signers::Signer;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, TransactionReceipt, U256};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
+mod anvil;
+mod artifacts;
+mod create2;
+mod options;
+mod tx_manager;
+mod wallet;
+
+pub use anvil::AnvilBuilder;
+use anvil::AnvilProcess;
+pub use artifacts::{ArtifactFormat, ArtifactSource, BundledArtifact, ContractArtifact};
+pub use create2::{
+    create2_call_factory_address, deploy_contract_artifact_create2,
+    deploy_contract_artifact_create2_call_with_receipt,
+    deploy_contract_artifact_create2_with_receipt, deploy_contract_create2,
+    ensure_create2_call_factory, ensure_create2_factory, predict_create2_address,
+    predict_create2_call_address, CREATE2_FACTORY_ADDRESS,
+};
+pub use options::DeployOptions;
+pub use tx_manager::{
+    FeePolicy, GasEscalation, TxManagerError, TxManagerMiddleware, TxManagerOptions,
+};
+pub use wallet::{KeystorePassword, WalletSource, DEFAULT_DERIVATION_PATH};
+
 /// Ethers library allows multiple signer backends and transports.
 /// For simplicity we use local wallet (basically private key) and
 /// HTTP transport in this crate.
 pub use utils::LocalWalletSignerMiddleware;
 
+/// Middleware returned by [`EthereumClient::impersonate`], accepted by the
+/// contract clients the same way as [`LocalWalletSignerMiddleware`].
+pub use utils::ImpersonatedMiddleware;
+
+/// Local signer behind the optional nonce manager, fee policy and gas escalator
+/// configured with [`EthereumClient::with_tx_manager`].
+pub type ManagedSignerMiddleware = TxManagerMiddleware<LocalWalletSignerMiddleware>;
+
 /// Sandbox is typically used for E2E scenarios so we need to speed things up
 const POLLING_INTERVAL_MS: u64 = 10;
 const ANVIL_DEFAULT_ENDPOINT: &str = "http://127.0.0.1:8545";
 const ANVIL_DEFAULT_CHAIN_ID: u64 = 31337;
 const ANVIL_DEFAULT_PRIVATE_KEY: &str =
     "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+bundled_artifact!(MULTICALL3, "Multicall3.json");
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    SerdeJson(#[from] serde_json::Error),
-    #[error("['bytecode']['object'] is not a string")]
-    BytecodeObject,
-    #[error(transparent)]
-    Hex(#[from] hex::FromHexError),
-    #[error("Failed to parse URL")]
-    UrlParser,
-    #[error(transparent)]
-    EthersContract(#[from] ContractError<LocalWalletSignerMiddleware>),
-    #[error(transparent)]
-    EthersProvider(#[from] ProviderError),
-    #[error("Invalid contract build artifacts: missing field `{0}`")]
-    ContractBuildArtifacts(&'static str),
-}
+/// Error of the sandbox and of the deployment functions (see [`utils::errors::Error`])
+pub type Error = utils::errors::Error<LocalWalletSignerMiddleware>;
 
 /// A convenient wrapper over an already running or spawned Anvil local devnet or ethereum
-#[allow(dead_code)]
 pub struct EthereumClient {
     /// If initialized keeps an Anvil instance to properly shutdown it at the end
-    client: Option<AnvilInstance>,
+    client: Option<AnvilProcess>,
     /// Pre-configured local signer
     signer: Arc<LocalWalletSignerMiddleware>,
+    /// Same signer with the transaction management layers
+    managed_signer: Arc<ManagedSignerMiddleware>,
 }
 
 impl EthereumClient {
@@ -57,6 +75,16 @@ impl EthereumClient {
         rpc_endpoint: Option<String>,
         priv_key: Option<String>,
         chain_id: Option<u64>,
+    ) -> Result<Self, Error> {
+        let priv_key = priv_key.unwrap_or_else(|| ANVIL_DEFAULT_PRIVATE_KEY.to_owned());
+        Self::attach_with_wallet(rpc_endpoint, WalletSource::PrivateKey(priv_key), chain_id)
+    }
+
+    /// Same as [`Self::attach`] with the signer loaded from a keystore, a mnemonic or a private key.
+    pub fn attach_with_wallet(
+        rpc_endpoint: Option<String>,
+        wallet: WalletSource,
+        chain_id: Option<u64>,
     ) -> Result<Self, Error> {
         let rpc_endpoint = rpc_endpoint.unwrap_or_else(|| {
             std::env::var("ETH_RPC_ENDPOINT")
@@ -69,18 +97,37 @@ impl EthereumClient {
             .map_err(|_| Error::UrlParser)?
             .interval(Duration::from_millis(POLLING_INTERVAL_MS));
 
-        let priv_key = priv_key.unwrap_or_else(|| ANVIL_DEFAULT_PRIVATE_KEY.to_owned());
-
-        let wallet: LocalWallet = priv_key.parse().expect("Failed to parse private key");
+        let wallet = wallet.into_wallet()?;
 
         let chain_id = chain_id.unwrap_or(ANVIL_DEFAULT_CHAIN_ID);
 
         let client = SignerMiddleware::new(provider.clone(), wallet.with_chain_id(chain_id));
 
-        Ok(Self {
-            client: None,
-            signer: Arc::new(client),
-        })
+        Ok(Self::new(None, client))
+    }
+
+    pub(crate) fn new(anvil: Option<AnvilProcess>, signer: LocalWalletSignerMiddleware) -> Self {
+        Self {
+            client: anvil,
+            managed_signer: Arc::new(TxManagerMiddleware::new(
+                signer.clone(),
+                TxManagerOptions::default(),
+            )),
+            signer: Arc::new(signer),
+        }
+    }
+
+    /// Enables the nonce manager, fee policy and gas escalator of the [`Self::managed_signer`].
+    /// Should be called once, before sending transactions with the managed signer.
+    ///
+    /// Deployments are not managed: [`deploy_contract`] and the deployment helpers of the client
+    /// crates take the [`Self::signer`], which sends from the same account. The nonce manager
+    /// does not see its transactions and keeps assigning its cached nonce, so the two signers must
+    /// not be used concurrently, and the raw signer must not send once the managed one has sent:
+    /// deploy the contracts first, then switch to the managed signer.
+    pub fn with_tx_manager(mut self, options: TxManagerOptions) -> Self {
+        self.managed_signer = Arc::new(TxManagerMiddleware::new((*self.signer).clone(), options));
+        self
     }
 
     /// Creates a new sandbox instance.
@@ -88,72 +135,260 @@ impl EthereumClient {
     ///     - `anvil_path` parameter (if specified)
     ///     - ${ANVIL_PATH} environment variable (if set)
     ///     - ~/.foundry/bin/anvil (default)
-    pub fn spawn(anvil_path: Option<PathBuf>) -> Self {
-        let anvil_path: PathBuf = anvil_path.unwrap_or_else(|| {
-            std::env::var("ANVIL_PATH")
-                .map(Into::into)
-                .ok()
-                .unwrap_or_else(|| dirs::home_dir().unwrap().join(".foundry/bin/anvil"))
-        });
-
-        // Will panic if invalid path
-        let anvil = Anvil::at(anvil_path).spawn();
-
-        let provider = Provider::<Http>::try_from(anvil.endpoint())
-            .expect("Failed to connect to Anvil")
-            .interval(Duration::from_millis(POLLING_INTERVAL_MS));
+    /// Use [`AnvilBuilder`] for more spawn options.
+    pub fn spawn(anvil_path: Option<PathBuf>) -> Result<Self, Error> {
+        match anvil_path {
+            Some(anvil_path) => AnvilBuilder::new().anvil_path(anvil_path).spawn(),
+            None => AnvilBuilder::new().spawn(),
+        }
+    }
 
-        let wallet: LocalWallet = anvil.keys()[0].clone().into();
-        let client =
-            SignerMiddleware::new(provider.clone(), wallet.with_chain_id(anvil.chain_id()));
+    /// Checks that the RPC endpoint is reachable and serves the chain the signer is configured for.
+    /// Should be called before building contract clients, transactions signed for
+    /// another chain would be rejected by the node.
+    pub async fn health_check(&self) -> Result<(), Error> {
+        let expected = self.signer.signer().chain_id();
+        let actual = self.signer.provider().get_chainid().await?;
 
-        Self {
-            client: Some(anvil),
-            signer: Arc::new(client),
+        if actual != U256::from(expected) {
+            return Err(Error::ChainIdMismatch { expected, actual });
         }
+        Ok(())
     }
 
     /// Returns local client configured for the running Anvil instance
     pub fn signer(&self) -> Arc<LocalWalletSignerMiddleware> {
         self.signer.clone()
     }
+
+    /// Returns the local signer with the transaction management layers configured
+    /// by [`Self::with_tx_manager`], to be passed to the contract clients
+    /// (deployments take the [`Self::signer`], see [`Self::with_tx_manager`]).
+    pub fn managed_signer(&self) -> Arc<ManagedSignerMiddleware> {
+        self.managed_signer.clone()
+    }
+
+    /// Returns a client configured with the prefunded account at `index`
+    /// (`signer_at(0)` is the same account as [`Self::signer`]).
+    /// Returns `None` if the account does not exist or the client is attached to an external node.
+    pub fn signer_at(&self, index: usize) -> Option<Arc<LocalWalletSignerMiddleware>> {
+        let wallet = self.client.as_ref()?.wallets.get(index)?.clone();
+        let chain_id = self.signer.signer().chain_id();
+
+        Some(Arc::new(SignerMiddleware::new(
+            self.signer.provider().clone(),
+            wallet.with_chain_id(chain_id),
+        )))
+    }
+
+    /// Returns the addresses of the prefunded accounts of the spawned Anvil instance
+    pub fn accounts(&self) -> &[Address] {
+        self.client
+            .as_ref()
+            .map(|anvil| anvil.addresses.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Takes a snapshot of the current chain state and returns its id.
+    /// Only supported by dev nodes (Anvil, Hardhat, Ganache).
+    pub async fn snapshot(&self) -> Result<U256, Error> {
+        Ok(self.signer.provider().request("evm_snapshot", ()).await?)
+    }
+
+    /// Reverts the chain state to a previously taken snapshot.
+    /// Returns `false` if the snapshot does not exist (note that a snapshot
+    /// can be reverted to only once, take a new one to revert again).
+    pub async fn revert(&self, snapshot_id: U256) -> Result<bool, Error> {
+        Ok(self
+            .signer
+            .provider()
+            .request("evm_revert", [snapshot_id])
+            .await?)
+    }
+
+    /// Moves the chain clock forward by `seconds`, effective starting from the next mined block.
+    pub async fn increase_time(&self, seconds: u64) -> Result<(), Error> {
+        self.signer
+            .provider()
+            .request::<_, serde_json::Value>("evm_increaseTime", [U256::from(seconds)])
+            .await?;
+        Ok(())
+    }
+
+    /// Sets the timestamp of the next mined block.
+    pub async fn set_next_block_timestamp(&self, timestamp: u64) -> Result<(), Error> {
+        self.signer
+            .provider()
+            .request::<_, serde_json::Value>("evm_setNextBlockTimestamp", [U256::from(timestamp)])
+            .await?;
+        Ok(())
+    }
+
+    /// Mines a single block.
+    pub async fn mine(&self) -> Result<(), Error> {
+        self.signer
+            .provider()
+            .request::<_, serde_json::Value>("evm_mine", ())
+            .await?;
+        Ok(())
+    }
+
+    /// Starts impersonating `address` and returns a middleware sending transactions on its behalf.
+    /// Only supported by Anvil, the account has to be funded (see [`Self::set_balance`])
+    /// unless it already holds enough ether to pay for gas.
+    pub async fn impersonate(
+        &self,
+        address: Address,
+    ) -> Result<Arc<ImpersonatedMiddleware>, Error> {
+        self.signer
+            .provider()
+            .request::<_, ()>("anvil_impersonateAccount", [address])
+            .await?;
+
+        Ok(Arc::new(
+            self.signer.provider().clone().with_sender(address),
+        ))
+    }
+
+    /// Stops impersonating `address`, transactions sent on its behalf will be rejected afterwards.
+    pub async fn stop_impersonating(&self, address: Address) -> Result<(), Error> {
+        self.signer
+            .provider()
+            .request::<_, ()>("anvil_stopImpersonatingAccount", [address])
+            .await?;
+        Ok(())
+    }
+
+    /// Overrides the ether balance of `address` (in wei).
+    pub async fn set_balance(&self, address: Address, balance: U256) -> Result<(), Error> {
+        self.signer
+            .provider()
+            .request::<_, ()>("anvil_setBalance", (address, balance))
+            .await?;
+        Ok(())
+    }
+
+    /// Moves the chain clock forward by `seconds` and mines a block so that
+    /// the new timestamp is visible to subsequent calls
+    /// (e.g. to get past the message cancellation delay of the core contract).
+    pub async fn fast_forward(&self, seconds: u64) -> Result<(), Error> {
+        self.increase_time(seconds).await?;
+        self.mine().await
+    }
+
+    /// Installs Multicall3 at its canonical address unless a contract is already deployed there,
+    /// so that view calls can be batched the same way as on public networks (see `utils::multicall`).
+    /// Only supported by Anvil.
+    pub async fn ensure_multicall3(&self, artifacts: &ArtifactSource) -> Result<(), Error> {
+        let provider = self.signer.provider();
+        if !provider.get_code(MULTICALL_ADDRESS, None).await?.is_empty() {
+            return Ok(());
+        }
+
+        let runtime_code = artifacts
+            .load(&MULTICALL3)?
+            .deployed_bytecode(&BTreeMap::new())?;
+        provider
+            .request::<_, ()>("anvil_setCode", (MULTICALL_ADDRESS, runtime_code))
+            .await?;
+
+        log::debug!("ℹ️  Multicall3 installed : {:?}", MULTICALL_ADDRESS);
+        Ok(())
+    }
 }
 
 /// Deploys new smart contract using:
-///     - Forge build artifacts (JSON file contents)
+///     - Forge, hardhat or single contract solc combined-json build artifacts (JSON file contents)
 ///     - Constructor args (use () if no args expected)
+///     - Deployment options (use `&DeployOptions::default()` for two confirmations and no overrides)
 pub async fn deploy_contract<T: Tokenize>(
     client: Arc<LocalWalletSignerMiddleware>,
     contract_build_artifacts: &str,
     contructor_args: T,
+    options: &DeployOptions,
+) -> Result<ContractInstance<Arc<LocalWalletSignerMiddleware>, LocalWalletSignerMiddleware>, Error>
+{
+    let artifact = ContractArtifact::parse(contract_build_artifacts)?;
+    deploy_contract_artifact(client, &artifact, contructor_args, options).await
+}
+
+/// Same as [`deploy_contract`] with already parsed build artifacts
+/// (e.g. a contract picked from solc combined-json output).
+/// The libraries are linked with the addresses set in the deployment options,
+/// the contract is recorded in their registry if it is named
+/// (see [`DeployOptions::deployment_entry`]).
+pub async fn deploy_contract_artifact<T: Tokenize>(
+    client: Arc<LocalWalletSignerMiddleware>,
+    artifact: &ContractArtifact,
+    contructor_args: T,
+    options: &DeployOptions,
 ) -> Result<ContractInstance<Arc<LocalWalletSignerMiddleware>, LocalWalletSignerMiddleware>, Error>
 {
-    let (abi, bytecode) = {
-        let mut artifacts: serde_json::Value = serde_json::from_str(contract_build_artifacts)?;
-
-        let abi_value = artifacts
-            .get_mut("abi")
-            .ok_or_else(|| Error::ContractBuildArtifacts("abi"))?
-            .take();
-        let bytecode_value = artifacts
-            .get_mut("bytecode")
-            .ok_or_else(|| Error::ContractBuildArtifacts("bytecode"))?
-            .get_mut("object")
-            .ok_or_else(|| Error::ContractBuildArtifacts("bytecode.object"))?
-            .take();
-
-        let abi = serde_json::from_value(abi_value)?;
-        let bytecode = Bytes::from_hex(bytecode_value.as_str().ok_or(Error::BytecodeObject)?)?;
-        (abi, bytecode)
-    };
-
-    let factory = ContractFactory::new(abi, bytecode, client.clone());
-
-    Ok(factory
+    let (contract, receipt) =
+        deploy_contract_artifact_with_receipt(client, artifact, contructor_args, options).await?;
+
+    if let Some(entry) = options.deployment_entry(artifact, contract.address(), Some(&receipt)) {
+        options.record(entry)?;
+    }
+    Ok(contract)
+}
+
+/// Same as [`deploy_contract_artifact`] returning the deployment receipt,
+/// the contract is not recorded in the registry.
+pub async fn deploy_contract_artifact_with_receipt<T: Tokenize>(
+    client: Arc<LocalWalletSignerMiddleware>,
+    artifact: &ContractArtifact,
+    contructor_args: T,
+    options: &DeployOptions,
+) -> Result<
+    (
+        ContractInstance<Arc<LocalWalletSignerMiddleware>, LocalWalletSignerMiddleware>,
+        TransactionReceipt,
+    ),
+    Error,
+> {
+    let bytecode = artifact.bytecode(&options.libraries)?;
+
+    let factory = ContractFactory::new(artifact.abi().clone(), bytecode, client.clone());
+    let deployer = factory
         .deploy(contructor_args)
-        .map_err(Into::<ContractError<LocalWalletSignerMiddleware>>::into)?
-        .confirmations(2_usize)
-        .send()
+        .map_err(Into::<ContractError<LocalWalletSignerMiddleware>>::into)?;
+    let abi = deployer.abi().clone();
+
+    let receipt = send_transaction_with_options(&client, deployer.tx, options).await?;
+    let address = receipt
+        .contract_address
+        .ok_or(ContractError::<LocalWalletSignerMiddleware>::ContractNotDeployed)?;
+
+    Ok((ContractInstance::new(address, abi, client), receipt))
+}
+
+/// Sends a transaction with the gas and fee overrides of `options` applied
+/// and waits for it to be confirmed as configured
+pub(crate) async fn send_transaction_with_options(
+    client: &LocalWalletSignerMiddleware,
+    mut tx: TypedTransaction,
+    options: &DeployOptions,
+) -> Result<TransactionReceipt, Error> {
+    options.apply(&mut tx);
+
+    let pending = client
+        .send_transaction(tx, None)
         .await
-        .map_err(Into::<ContractError<LocalWalletSignerMiddleware>>::into)?)
+        .map_err(ContractError::<LocalWalletSignerMiddleware>::from_middleware_error)?;
+    let tx_hash = pending.tx_hash();
+
+    let mut pending = pending.confirmations(options.confirmations);
+    if let Some(polling_interval) = options.polling_interval {
+        pending = pending.interval(polling_interval);
+    }
+
+    let receipt = match options.timeout {
+        Some(timeout) => tokio::time::timeout(timeout, pending)
+            .await
+            .map_err(|_| Error::Timeout(tx_hash))?,
+        None => pending.await,
+    }?;
+
+    receipt.ok_or(Error::Dropped(tx_hash))
 }
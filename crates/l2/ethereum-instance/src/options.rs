@@ -0,0 +1,206 @@
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, TransactionReceipt, U256};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::{ArtifactSource, ContractArtifact, Error};
+use utils::registry::{DeploymentEntry, DeploymentRegistry};
+
+/// Number of confirmations awaited by default when deploying a contract
+const DEFAULT_CONFIRMATIONS: usize = 2;
+
+/// Options for [`crate::deploy_contract`] and the other deployment helpers.
+/// The default matches the historical behaviour: two confirmations, the provider
+/// polling interval, no timeout and gas / fees estimated by the node.
+#[derive(Debug, Clone)]
+pub struct DeployOptions {
+    pub(crate) confirmations: usize,
+    pub(crate) polling_interval: Option<Duration>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) gas_limit: Option<U256>,
+    pub(crate) gas_price: Option<U256>,
+    pub(crate) max_fee_per_gas: Option<U256>,
+    pub(crate) max_priority_fee_per_gas: Option<U256>,
+    pub(crate) libraries: BTreeMap<String, Address>,
+    pub(crate) artifacts: ArtifactSource,
+    pub(crate) registry: Option<DeploymentRegistry>,
+    pub(crate) registry_name: Option<String>,
+}
+
+impl Default for DeployOptions {
+    fn default() -> Self {
+        Self {
+            confirmations: DEFAULT_CONFIRMATIONS,
+            polling_interval: None,
+            timeout: None,
+            gas_limit: None,
+            gas_price: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            libraries: BTreeMap::new(),
+            artifacts: ArtifactSource::default(),
+            registry: None,
+            registry_name: None,
+        }
+    }
+}
+
+impl DeployOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of blocks to wait for, counting the one including the deployment: 0 and 1
+    /// both return as soon as it is included (use them with Anvil instant mining, which
+    /// does not mine blocks without transactions).
+    pub fn confirmations(mut self, confirmations: usize) -> Self {
+        self.confirmations = confirmations;
+        self
+    }
+
+    /// Interval between receipt lookups, defaults to the provider polling interval.
+    pub fn polling_interval(mut self, polling_interval: Duration) -> Self {
+        self.polling_interval = Some(polling_interval);
+        self
+    }
+
+    /// Maximum time to wait for the deployment to be confirmed.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Gas limit of the deployment transaction, estimated by the node if not set.
+    pub fn gas_limit(mut self, gas_limit: impl Into<U256>) -> Self {
+        self.gas_limit = Some(gas_limit.into());
+        self
+    }
+
+    /// Gas price of legacy transactions (sets both fee caps of EIP-1559 transactions).
+    pub fn gas_price(mut self, gas_price: impl Into<U256>) -> Self {
+        self.gas_price = Some(gas_price.into());
+        self
+    }
+
+    /// Max fee per gas of EIP-1559 transactions.
+    pub fn max_fee_per_gas(mut self, max_fee_per_gas: impl Into<U256>) -> Self {
+        self.max_fee_per_gas = Some(max_fee_per_gas.into());
+        self
+    }
+
+    /// Max priority fee per gas of EIP-1559 transactions.
+    pub fn max_priority_fee_per_gas(mut self, max_priority_fee_per_gas: impl Into<U256>) -> Self {
+        self.max_priority_fee_per_gas = Some(max_priority_fee_per_gas.into());
+        self
+    }
+
+    /// Address of a library linked into the deployed bytecode, by name (`Name`)
+    /// or by fully qualified name (`path/File.sol:Name`) if several libraries share a name.
+    pub fn library(mut self, name: impl Into<String>, address: Address) -> Self {
+        self.libraries.insert(name.into(), address);
+        self
+    }
+
+    /// Source of the build artifacts of the contracts deployed by the deployment helpers
+    /// (proxies, StarkWare contracts), the embedded ones by default.
+    pub fn artifacts(mut self, artifacts: ArtifactSource) -> Self {
+        self.artifacts = artifacts;
+        self
+    }
+
+    /// Build artifacts loaded by the deployment helpers
+    pub fn artifact_source(&self) -> &ArtifactSource {
+        &self.artifacts
+    }
+
+    /// Registry the named contracts are recorded in once deployed (see [`crate::ContractArtifact::name`])
+    pub fn registry(mut self, registry: DeploymentRegistry) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Name the deployed contract is recorded under in the registry instead of the name of its
+    /// artifacts, e.g. to record several deployments of the same contract or forge artifacts
+    /// (which are unnamed).
+    pub fn registry_name(mut self, registry_name: impl Into<String>) -> Self {
+        self.registry_name = Some(registry_name.into());
+        self
+    }
+
+    /// Registry entry of a contract deployed from `artifact` at `address`, `None` if there is no
+    /// registry. Contracts deployed from unnamed artifacts are not recorded (a warning is logged)
+    /// unless [`Self::registry_name`] is set.
+    pub fn deployment_entry(
+        &self,
+        artifact: &ContractArtifact,
+        address: Address,
+        receipt: Option<&TransactionReceipt>,
+    ) -> Option<DeploymentEntry> {
+        self.registry.as_ref()?;
+        let Some(name) = self.registry_name.as_deref().or(artifact.name()) else {
+            log::warn!(
+                "⚠️  Contract deployed at {:?} not recorded in the registry: its artifacts are unnamed, set `DeployOptions::registry_name`",
+                address
+            );
+            return None;
+        };
+        Some(artifact.deployment_entry(name, address, receipt))
+    }
+
+    /// Records a deployed contract in the registry, if any
+    pub fn record(&self, entry: DeploymentEntry) -> Result<(), Error> {
+        if let Some(registry) = &self.registry {
+            registry.record(entry)?;
+        }
+        Ok(())
+    }
+
+    /// Applies gas and fee overrides to the deployment transaction
+    pub(crate) fn apply(&self, tx: &mut TypedTransaction) {
+        if let Some(gas_limit) = self.gas_limit {
+            tx.set_gas(gas_limit);
+        }
+        if let Some(gas_price) = self.gas_price {
+            tx.set_gas_price(gas_price);
+        }
+        if let TypedTransaction::Eip1559(tx) = tx {
+            if let Some(max_fee_per_gas) = self.max_fee_per_gas {
+                tx.max_fee_per_gas = Some(max_fee_per_gas);
+            }
+            if let Some(max_priority_fee_per_gas) = self.max_priority_fee_per_gas {
+                tx.max_priority_fee_per_gas = Some(max_priority_fee_per_gas);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTIFACT: &str = r#"{ "abi": [], "bytecode": { "object": "0x6000" } }"#;
+
+    #[test]
+    fn test_deployment_entry_name() {
+        let address = Address::repeat_byte(0x11);
+        let artifact = ContractArtifact::parse(ARTIFACT).unwrap();
+        let registry = DeploymentRegistry::in_memory();
+
+        let options = DeployOptions::new();
+        assert_eq!(options.deployment_entry(&artifact, address, None), None);
+
+        // forge artifacts are unnamed
+        let options = DeployOptions::new().registry(registry.clone());
+        assert_eq!(options.deployment_entry(&artifact, address, None), None);
+        let named = artifact.clone().with_name("ERC20");
+        let entry = options.deployment_entry(&named, address, None).unwrap();
+        assert_eq!(entry.name, "ERC20");
+
+        let options = options.registry_name("ETH");
+        let entry = options.deployment_entry(&named, address, None).unwrap();
+        assert_eq!(entry.name, "ETH");
+        assert_eq!(entry.artifact_hash, artifact.hash());
+        options.record(entry.clone()).unwrap();
+        assert_eq!(registry.get("ETH"), Some(entry));
+    }
+}
@@ -0,0 +1,559 @@
+use async_trait::async_trait;
+use ethers::providers::{Middleware, MiddlewareError, PendingTransaction};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{BlockId, BlockNumber, TxHash, U256};
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Minimum fee bump accepted by the nodes to replace a pending transaction
+const MIN_BUMP_PERCENT: u64 = 10;
+
+/// EIP-1559 fee policy applied to the transactions which don't set their fees
+#[derive(Debug, Clone, Default)]
+pub struct FeePolicy {
+    max_fee_per_gas_cap: Option<U256>,
+    max_priority_fee_per_gas: Option<U256>,
+}
+
+impl FeePolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Upper bound of the max fee per gas (and of the gas price of legacy transactions),
+    /// also applies to the escalated fees.
+    pub fn max_fee_per_gas_cap(mut self, cap: impl Into<U256>) -> Self {
+        self.max_fee_per_gas_cap = Some(cap.into());
+        self
+    }
+
+    /// Priority fee per gas, estimated by the node if not set.
+    pub fn max_priority_fee_per_gas(mut self, priority_fee: impl Into<U256>) -> Self {
+        self.max_priority_fee_per_gas = Some(priority_fee.into());
+        self
+    }
+}
+
+/// Re-broadcasts transactions not mined after `interval` with fees bumped by `bump_percent`,
+/// at most `max_bumps` times. With gas escalation, `send_transaction` only returns once one
+/// of the versions of the transaction is mined, with the pending transaction of that version.
+#[derive(Debug, Clone)]
+pub struct GasEscalation {
+    interval: Duration,
+    bump_percent: u64,
+    max_bumps: usize,
+}
+
+impl GasEscalation {
+    /// `bump_percent` is raised to 10% if lower (minimum replacement bump of the nodes).
+    pub fn new(interval: Duration, bump_percent: u64, max_bumps: usize) -> Self {
+        Self {
+            interval,
+            bump_percent: bump_percent.max(MIN_BUMP_PERCENT),
+            max_bumps,
+        }
+    }
+}
+
+/// Options of [`TxManagerMiddleware`], nothing enabled by default
+#[derive(Debug, Clone, Default)]
+pub struct TxManagerOptions {
+    nonce_manager: bool,
+    fee_policy: Option<FeePolicy>,
+    gas_escalation: Option<GasEscalation>,
+}
+
+impl TxManagerOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assigns nonces locally so that concurrent transactions from the same account don't collide.
+    pub fn nonce_manager(mut self, enabled: bool) -> Self {
+        self.nonce_manager = enabled;
+        self
+    }
+
+    pub fn fee_policy(mut self, fee_policy: FeePolicy) -> Self {
+        self.fee_policy = Some(fee_policy);
+        self
+    }
+
+    pub fn gas_escalation(mut self, gas_escalation: GasEscalation) -> Self {
+        self.gas_escalation = Some(gas_escalation);
+        self
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TxManagerError<M: Middleware> {
+    #[error("{0}")]
+    MiddlewareError(M::Error),
+    #[error("Transaction has no sender")]
+    NoSender,
+    #[error("Failed to re-broadcast transaction {0:?} with bumped fees: {1}")]
+    Rebroadcast(TxHash, M::Error),
+}
+
+impl<M: Middleware> MiddlewareError for TxManagerError<M> {
+    type Inner = M::Error;
+
+    fn from_err(src: M::Error) -> Self {
+        TxManagerError::MiddlewareError(src)
+    }
+
+    fn as_inner(&self) -> Option<&Self::Inner> {
+        match self {
+            TxManagerError::MiddlewareError(e) | TxManagerError::Rebroadcast(_, e) => Some(e),
+            TxManagerError::NoSender => None,
+        }
+    }
+}
+
+/// Middleware layering optional nonce management, fee policy and gas escalation
+/// on top of `M` (see [`TxManagerOptions`]).
+#[derive(Debug)]
+pub struct TxManagerMiddleware<M> {
+    inner: M,
+    options: TxManagerOptions,
+    /// Next nonce to assign, fetched from the node when unset
+    nonce: Mutex<Option<U256>>,
+}
+
+impl<M: Middleware + 'static> TxManagerMiddleware<M> {
+    pub fn new(inner: M, options: TxManagerOptions) -> Self {
+        Self {
+            inner,
+            options,
+            nonce: Mutex::new(None),
+        }
+    }
+
+    async fn next_nonce(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<U256, TxManagerError<M>> {
+        let mut nonce = self.nonce.lock().await;
+        let next = match *nonce {
+            Some(next) => next,
+            None => {
+                let sender = tx
+                    .from()
+                    .copied()
+                    .or_else(|| self.inner.default_sender())
+                    .ok_or(TxManagerError::NoSender)?;
+                self.inner
+                    .get_transaction_count(sender, block.or(Some(BlockNumber::Pending.into())))
+                    .await
+                    .map_err(MiddlewareError::from_err)?
+            }
+        };
+        *nonce = Some(next + 1);
+        Ok(next)
+    }
+
+    async fn apply_fee_policy(&self, tx: &mut TypedTransaction) -> Result<(), TxManagerError<M>> {
+        let Some(policy) = &self.options.fee_policy else {
+            return Ok(());
+        };
+
+        match tx {
+            TypedTransaction::Eip1559(inner_tx) => {
+                if inner_tx.max_fee_per_gas.is_some() && inner_tx.max_priority_fee_per_gas.is_some()
+                {
+                    return Ok(());
+                }
+                let (estimated_max_fee, estimated_priority_fee) = self
+                    .inner
+                    .estimate_eip1559_fees(None)
+                    .await
+                    .map_err(MiddlewareError::from_err)?;
+                let priority_fee = policy
+                    .max_priority_fee_per_gas
+                    .unwrap_or(estimated_priority_fee);
+                // The estimated max fee already includes the estimated priority fee
+                let mut max_fee =
+                    estimated_max_fee.saturating_sub(estimated_priority_fee) + priority_fee;
+                if let Some(cap) = policy.max_fee_per_gas_cap {
+                    max_fee = max_fee.min(cap);
+                }
+                inner_tx.max_fee_per_gas.get_or_insert(max_fee);
+                inner_tx
+                    .max_priority_fee_per_gas
+                    .get_or_insert(priority_fee.min(max_fee));
+            }
+            _ => {
+                if tx.gas_price().is_none() {
+                    let mut gas_price = self
+                        .inner
+                        .get_gas_price()
+                        .await
+                        .map_err(MiddlewareError::from_err)?;
+                    if let Some(cap) = policy.max_fee_per_gas_cap {
+                        gas_price = gas_price.min(cap);
+                    }
+                    tx.set_gas_price(gas_price);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Re-broadcasts `tx` with bumped fees until one of its versions is mined and returns
+    /// the pending transaction of the mined version, or of the last one if they were all dropped
+    async fn escalate(
+        &self,
+        mut tx: TypedTransaction,
+        block: Option<BlockId>,
+        tx_hash: TxHash,
+        escalation: &GasEscalation,
+    ) -> Result<PendingTransaction<'_, M::Provider>, TxManagerError<M>> {
+        let cap = self
+            .options
+            .fee_policy
+            .as_ref()
+            .and_then(|policy| policy.max_fee_per_gas_cap);
+        // Hashes of all the broadcasted versions, any of them can be mined
+        let mut hashes = vec![tx_hash];
+        let mut bumping = true;
+
+        loop {
+            tokio::time::sleep(escalation.interval).await;
+            if let Some(mined) = self.mined_version(&hashes).await? {
+                return Ok(PendingTransaction::new(mined, self.provider()));
+            }
+
+            if bumping && hashes.len() > escalation.max_bumps {
+                log::warn!(
+                    "⚠️  Transaction {:?} still pending after {} fee bumps",
+                    hashes.last(),
+                    escalation.max_bumps
+                );
+                bumping = false;
+            }
+            if bumping && !bump_fees(&mut tx, escalation.bump_percent, cap) {
+                log::warn!(
+                    "⚠️  Transaction {:?} still pending, fees capped at {:?}",
+                    hashes.last(),
+                    cap
+                );
+                bumping = false;
+            }
+            if !bumping {
+                if !self.is_known(&hashes).await? {
+                    let last = hashes[hashes.len() - 1];
+                    return Ok(PendingTransaction::new(last, self.provider()));
+                }
+                continue;
+            }
+
+            match self.inner.send_transaction(tx.clone(), block).await {
+                Ok(replacement) => {
+                    log::debug!(
+                        "ℹ️  Transaction {:?} re-broadcasted as {:?}",
+                        hashes.last(),
+                        replacement.tx_hash()
+                    );
+                    hashes.push(replacement.tx_hash());
+                }
+                Err(err) => {
+                    // The nonce may have been consumed by one of the versions in the meantime
+                    if let Some(mined) = self.mined_version(&hashes).await? {
+                        return Ok(PendingTransaction::new(mined, self.provider()));
+                    }
+                    return Err(TxManagerError::Rebroadcast(hashes[hashes.len() - 1], err));
+                }
+            }
+        }
+    }
+
+    async fn mined_version(&self, hashes: &[TxHash]) -> Result<Option<TxHash>, TxManagerError<M>> {
+        for hash in hashes {
+            let receipt = self
+                .inner
+                .get_transaction_receipt(*hash)
+                .await
+                .map_err(MiddlewareError::from_err)?;
+            if receipt.is_some() {
+                return Ok(Some(*hash));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Whether one of the versions is still known by the node (pending or mined)
+    async fn is_known(&self, hashes: &[TxHash]) -> Result<bool, TxManagerError<M>> {
+        for hash in hashes {
+            let transaction = self
+                .inner
+                .get_transaction(*hash)
+                .await
+                .map_err(MiddlewareError::from_err)?;
+            if transaction.is_some() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+#[async_trait]
+impl<M: Middleware + 'static> Middleware for TxManagerMiddleware<M> {
+    type Error = TxManagerError<M>;
+    type Provider = M::Provider;
+    type Inner = M;
+
+    fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    async fn fill_transaction(
+        &self,
+        tx: &mut TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<(), Self::Error> {
+        if self.options.nonce_manager && tx.nonce().is_none() {
+            tx.set_nonce(self.next_nonce(tx, block).await?);
+        }
+        self.apply_fee_policy(tx).await?;
+
+        self.inner
+            .fill_transaction(tx, block)
+            .await
+            .map_err(MiddlewareError::from_err)
+    }
+
+    async fn send_transaction<T: Into<TypedTransaction> + Send + Sync>(
+        &self,
+        tx: T,
+        block: Option<BlockId>,
+    ) -> Result<PendingTransaction<'_, Self::Provider>, Self::Error> {
+        let mut tx = tx.into();
+        self.fill_transaction(&mut tx, block).await?;
+
+        let pending = match self.inner.send_transaction(tx.clone(), block).await {
+            Ok(pending) => pending,
+            Err(err) => {
+                // Resync the nonce with the node on the next transaction
+                if self.options.nonce_manager {
+                    *self.nonce.lock().await = None;
+                }
+                return Err(MiddlewareError::from_err(err));
+            }
+        };
+
+        match &self.options.gas_escalation {
+            Some(escalation) => {
+                self.escalate(tx, block, pending.tx_hash(), escalation)
+                    .await
+            }
+            None => Ok(pending),
+        }
+    }
+}
+
+/// Bumps the fees by `bump_percent`, returns `false` if the cap prevents it (the nodes would
+/// reject the replacement)
+fn bump_fees(tx: &mut TypedTransaction, bump_percent: u64, cap: Option<U256>) -> bool {
+    let bump = |value: U256| {
+        let bumped = value * (100 + bump_percent) / 100;
+        cap.map_or(bumped, |cap| bumped.min(cap))
+    };
+    // Same check as the nodes on the fees of a replacement
+    let accepted = |old: U256, new: U256| new >= old * (100 + MIN_BUMP_PERCENT) / 100;
+
+    match tx {
+        TypedTransaction::Eip1559(inner_tx) => {
+            let (Some(max_fee), Some(priority_fee)) =
+                (inner_tx.max_fee_per_gas, inner_tx.max_priority_fee_per_gas)
+            else {
+                return false;
+            };
+            let bumped_max_fee = bump(max_fee);
+            let bumped_priority_fee = bump(priority_fee).min(bumped_max_fee);
+            if !accepted(max_fee, bumped_max_fee) || !accepted(priority_fee, bumped_priority_fee) {
+                return false;
+            }
+            inner_tx.max_fee_per_gas = Some(bumped_max_fee);
+            inner_tx.max_priority_fee_per_gas = Some(bumped_priority_fee);
+            true
+        }
+        _ => {
+            let Some(gas_price) = tx.gas_price() else {
+                return false;
+            };
+            let bumped = bump(gas_price);
+            if !accepted(gas_price, bumped) {
+                return false;
+            }
+            tx.set_gas_price(bumped);
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::providers::{MockProvider, Provider, ProviderError};
+    use ethers::types::{
+        Address, NameOrAddress, Transaction, TransactionReceipt, TransactionRequest, H256,
+    };
+    use std::sync::{Arc, Mutex as SyncMutex};
+
+    /// Node recording the sent transactions, `mined` being the index of the one to mine
+    #[derive(Debug)]
+    struct MockNode {
+        provider: Provider<MockProvider>,
+        nonce_lookups: SyncMutex<usize>,
+        sent: SyncMutex<Vec<TypedTransaction>>,
+        mined: Option<usize>,
+    }
+
+    impl MockNode {
+        fn new(mined: Option<usize>) -> Self {
+            Self {
+                provider: Provider::new(MockProvider::new()),
+                nonce_lookups: SyncMutex::new(0),
+                sent: SyncMutex::new(Vec::new()),
+                mined,
+            }
+        }
+
+        fn hash(index: usize) -> TxHash {
+            H256::from_low_u64_be(index as u64 + 1)
+        }
+
+        fn index(&self, hash: TxHash) -> Option<usize> {
+            let sent = self.sent.lock().unwrap().len();
+            (0..sent).find(|index| Self::hash(*index) == hash)
+        }
+    }
+
+    #[async_trait]
+    impl Middleware for MockNode {
+        type Error = ProviderError;
+        type Provider = MockProvider;
+        type Inner = Provider<MockProvider>;
+
+        fn inner(&self) -> &Provider<MockProvider> {
+            &self.provider
+        }
+
+        fn default_sender(&self) -> Option<Address> {
+            Some(Address::repeat_byte(0x01))
+        }
+
+        async fn get_transaction_count<T: Into<NameOrAddress> + Send + Sync>(
+            &self,
+            _from: T,
+            _block: Option<BlockId>,
+        ) -> Result<U256, ProviderError> {
+            *self.nonce_lookups.lock().unwrap() += 1;
+            tokio::task::yield_now().await;
+            Ok(5.into())
+        }
+
+        async fn fill_transaction(
+            &self,
+            _tx: &mut TypedTransaction,
+            _block: Option<BlockId>,
+        ) -> Result<(), ProviderError> {
+            Ok(())
+        }
+
+        async fn send_transaction<T: Into<TypedTransaction> + Send + Sync>(
+            &self,
+            tx: T,
+            _block: Option<BlockId>,
+        ) -> Result<PendingTransaction<'_, MockProvider>, ProviderError> {
+            let mut sent = self.sent.lock().unwrap();
+            sent.push(tx.into());
+            Ok(PendingTransaction::new(
+                Self::hash(sent.len() - 1),
+                &self.provider,
+            ))
+        }
+
+        async fn get_transaction<T: Send + Sync + Into<TxHash>>(
+            &self,
+            hash: T,
+        ) -> Result<Option<Transaction>, ProviderError> {
+            Ok(self.index(hash.into()).map(|_| Transaction::default()))
+        }
+
+        async fn get_transaction_receipt<T: Send + Sync + Into<TxHash>>(
+            &self,
+            hash: T,
+        ) -> Result<Option<TransactionReceipt>, ProviderError> {
+            let index = self.index(hash.into());
+            Ok((index.is_some() && index == self.mined).then(TransactionReceipt::default))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_nonce_manager_concurrent_sends() {
+        let manager = Arc::new(TxManagerMiddleware::new(
+            MockNode::new(None),
+            TxManagerOptions::new().nonce_manager(true),
+        ));
+
+        let sends: Vec<_> = (0..8)
+            .map(|_| {
+                let manager = manager.clone();
+                tokio::spawn(async move {
+                    let pending = manager.send_transaction(TransactionRequest::new(), None);
+                    pending.await.map(|pending| pending.tx_hash())
+                })
+            })
+            .collect();
+        for send in sends {
+            send.await.unwrap().unwrap();
+        }
+
+        let mut nonces: Vec<_> = manager
+            .inner()
+            .sent
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|tx| tx.nonce().copied().unwrap())
+            .collect();
+        nonces.sort();
+        assert_eq!(nonces, (5..13).map(U256::from).collect::<Vec<_>>());
+        assert_eq!(*manager.inner().nonce_lookups.lock().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gas_escalation_follows_replacements() {
+        let escalation = GasEscalation::new(Duration::from_millis(1), 20, 3);
+        let options = TxManagerOptions::new()
+            .fee_policy(FeePolicy::new().max_fee_per_gas_cap(140))
+            .gas_escalation(escalation);
+
+        // The first replacement is mined: its hash is returned
+        let manager = TxManagerMiddleware::new(MockNode::new(Some(1)), options.clone());
+        let tx = TransactionRequest::new().gas_price(100);
+        let pending = manager.send_transaction(tx.clone(), None).await.unwrap();
+        assert_eq!(pending.tx_hash(), MockNode::hash(1));
+        let gas_prices: Vec<_> = manager
+            .inner()
+            .sent
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|tx| tx.gas_price().unwrap())
+            .collect();
+        assert_eq!(gas_prices, vec![100.into(), 120.into()]);
+
+        // Bumps are capped, the capped version is mined
+        let manager = TxManagerMiddleware::new(MockNode::new(Some(2)), options);
+        let pending = manager.send_transaction(tx, None).await.unwrap();
+        assert_eq!(pending.tx_hash(), MockNode::hash(2));
+        assert_eq!(
+            manager.inner().sent.lock().unwrap()[2].gas_price(),
+            Some(140.into())
+        );
+    }
+}
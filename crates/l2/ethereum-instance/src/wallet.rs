@@ -0,0 +1,116 @@
+use ethers::signers::coins_bip39::English;
+use ethers::signers::{LocalWallet, MnemonicBuilder};
+use ethers::types::PathOrString;
+use std::path::PathBuf;
+
+use crate::Error;
+
+/// Derivation path of the Ethereum accounts, the account index is appended to it
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/60'/0'/0";
+
+/// Where the password of an encrypted JSON keystore comes from
+#[derive(Clone)]
+pub enum KeystorePassword {
+    /// Password passed as is
+    Plain(String),
+    /// Name of the environment variable holding the password
+    Env(String),
+    /// File containing the password (trailing newline ignored)
+    File(PathBuf),
+}
+
+/// Local wallet used by [`crate::EthereumClient`] to sign transactions
+#[derive(Clone)]
+pub enum WalletSource {
+    /// Hex encoded private key
+    PrivateKey(String),
+    /// Encrypted JSON keystore (as written by `cast wallet import` or geth)
+    Keystore {
+        path: PathBuf,
+        password: KeystorePassword,
+    },
+    /// BIP-39 mnemonic, the key is derived at `{derivation_path}/{index}`
+    /// (`derivation_path` defaults to [`DEFAULT_DERIVATION_PATH`])
+    Mnemonic {
+        phrase: String,
+        derivation_path: Option<String>,
+        index: u32,
+    },
+}
+
+impl KeystorePassword {
+    fn resolve(&self) -> Result<String, Error> {
+        match self {
+            Self::Plain(password) => Ok(password.clone()),
+            Self::Env(name) => {
+                std::env::var(name).map_err(|_| Error::KeystorePasswordEnv(name.clone()))
+            }
+            Self::File(path) => std::fs::read_to_string(path)
+                .map(|password| password.trim_end_matches(['\r', '\n']).to_owned())
+                .map_err(|e| Error::KeystorePasswordFile(path.clone(), e)),
+        }
+    }
+}
+
+impl WalletSource {
+    /// Loads the wallet (no chain id set).
+    pub fn into_wallet(self) -> Result<LocalWallet, Error> {
+        match self {
+            Self::PrivateKey(priv_key) => priv_key.parse().map_err(Error::InvalidPrivateKey),
+            Self::Keystore { path, password } => {
+                LocalWallet::decrypt_keystore(path, password.resolve()?).map_err(Error::Keystore)
+            }
+            Self::Mnemonic {
+                phrase,
+                derivation_path,
+                index,
+            } => {
+                let derivation_path = format!(
+                    "{}/{}",
+                    derivation_path
+                        .as_deref()
+                        .unwrap_or(DEFAULT_DERIVATION_PATH)
+                        .trim_end_matches('/'),
+                    index
+                );
+                MnemonicBuilder::<English>::default()
+                    .phrase(PathOrString::String(phrase))
+                    .derivation_path(&derivation_path)
+                    .and_then(|builder| builder.build())
+                    .map_err(Error::Mnemonic)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WalletSource;
+    use ethers::signers::Signer;
+
+    #[test]
+    fn test_mnemonic_derivation_matches_anvil_accounts() {
+        let phrase = "test test test test test test test test test test test junk";
+        let wallet = |index| {
+            WalletSource::Mnemonic {
+                phrase: phrase.to_owned(),
+                derivation_path: None,
+                index,
+            }
+            .into_wallet()
+            .unwrap()
+        };
+        assert_eq!(
+            wallet(0).address(),
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+                .parse()
+                .unwrap()
+        );
+        assert_eq!(
+            wallet(1).address(),
+            "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"
+                .parse()
+                .unwrap()
+        );
+    }
+}
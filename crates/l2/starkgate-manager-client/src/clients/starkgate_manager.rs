@@ -1,52 +1,75 @@
 use std::sync::Arc;
 
 use crate::interfaces::manager::StarkgateManager;
-use utils::{LocalWalletSignerMiddleware, StarknetContractClient};
+use starknet_proxy_client::implementation::resolve_implementation;
+use utils::errors::Error;
+use utils::registry::DeploymentEntry;
+use utils::{LocalWalletSignerMiddleware, ReadOnly, ReadWrite, StarknetContractClient};
 
+use ethers::middleware::Middleware;
 use ethers::types::Address;
 use starknet_proxy_client::clients::proxy_5_0_0::ProxySupport5_0_0;
 
 #[derive(Clone)]
-pub struct StarkgateManagerContractClient {
-    manager: StarkgateManager<LocalWalletSignerMiddleware>,
-    proxy_support: ProxySupport5_0_0<LocalWalletSignerMiddleware>,
+pub struct StarkgateManagerContractClient<M = LocalWalletSignerMiddleware> {
+    manager: StarkgateManager<M>,
+    proxy_support: ProxySupport5_0_0<M>,
     manager_implementation: Address,
 }
 
-impl StarkgateManagerContractClient {
-    pub fn new(
-        address: Address,
-        client: Arc<LocalWalletSignerMiddleware>,
-        implementation_address: Address,
-    ) -> Self {
+impl<M: Middleware> StarkgateManagerContractClient<M> {
+    pub fn new(address: Address, client: Arc<M>, implementation_address: Address) -> Self {
         Self {
             manager: StarkgateManager::new(address, client.clone()),
             proxy_support: ProxySupport5_0_0::new(address, client.clone()),
             manager_implementation: implementation_address,
         }
     }
+
+    /// Client of a deployment behind a proxy, its implementation being read on-chain
+    /// (see [`resolve_implementation`])
+    pub async fn attach(address: Address, client: Arc<M>) -> Result<Self, Error<M>> {
+        let implementation_address = resolve_implementation(address, client.clone()).await?;
+        Ok(Self::new(address, client, implementation_address))
+    }
+
+    /// Client of a deployment recorded in a [`utils::registry::DeploymentRegistry`]
+    pub fn from_registry_entry(entry: &DeploymentEntry, client: Arc<M>) -> Self {
+        Self::new(entry.address, client, entry.implementation)
+    }
+
+    /// Read-only client, typically over a plain `Provider`: only view methods can be called.
+    pub fn read_only(
+        address: Address,
+        client: Arc<M>,
+        implementation_address: Address,
+    ) -> ReadOnly<Self> {
+        ReadOnly::new(Self::new(address, client, implementation_address))
+    }
 }
 
-impl AsRef<StarkgateManager<LocalWalletSignerMiddleware>> for StarkgateManagerContractClient {
-    fn as_ref(&self) -> &StarkgateManager<LocalWalletSignerMiddleware> {
+impl<M> AsRef<StarkgateManager<M>> for StarkgateManagerContractClient<M> {
+    fn as_ref(&self) -> &StarkgateManager<M> {
         &self.manager
     }
 }
 
-impl AsRef<ProxySupport5_0_0<LocalWalletSignerMiddleware>> for StarkgateManagerContractClient {
-    fn as_ref(&self) -> &ProxySupport5_0_0<LocalWalletSignerMiddleware> {
+impl<M> AsRef<ProxySupport5_0_0<M>> for StarkgateManagerContractClient<M> {
+    fn as_ref(&self) -> &ProxySupport5_0_0<M> {
         &self.proxy_support
     }
 }
 
-impl StarknetContractClient for StarkgateManagerContractClient {
+impl<M> ReadWrite for StarkgateManagerContractClient<M> {}
+
+impl<M: Middleware> StarknetContractClient<M> for StarkgateManagerContractClient<M> {
     fn address(&self) -> Address {
         self.manager.address()
     }
     fn implementation_address(&self) -> Address {
         self.manager_implementation
     }
-    fn client(&self) -> Arc<LocalWalletSignerMiddleware> {
+    fn client(&self) -> Arc<M> {
         self.manager.client()
     }
 }
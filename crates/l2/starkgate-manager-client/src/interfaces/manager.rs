@@ -1,19 +1,21 @@
 use async_trait::async_trait;
+use ethers::contract::ContractCall;
+use ethers::types::transaction::eip2718::TypedTransaction;
 use ethers::{
-    contract::ContractError,
     prelude::abigen,
     providers::Middleware,
-    types::{TransactionReceipt, H160, U256},
+    types::{H160, U256},
 };
 
 use utils::errors::Error;
+use utils::transaction::{
+    send_call, simulate_call, SimulationOptions, SimulationOutcome, TransactionOutcome,
+};
+use utils::ContractReader;
 
 type Address = H160;
 
-abigen!(
-    StarkgateManager,
-    "../../../artifacts/starkgate-contracts/StarkgateManager.json",
-);
+abigen!(StarkgateManager, "abi/StarkgateManager.json");
 
 #[async_trait]
 pub trait StarkgateManagerTrait<M: Middleware> {
@@ -21,19 +23,40 @@ pub trait StarkgateManagerTrait<M: Middleware> {
         &self,
         token: Address,
         bridge: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
-    async fn deactivate_token(
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_add_existing_bridge(
+        &self,
+        token: Address,
+        bridge: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_add_existing_bridge(&self, token: Address, bridge: Address) -> TypedTransaction;
+    async fn deactivate_token(&self, token: Address) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_deactivate_token(
         &self,
         token: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
-    async fn block_token(&self, token: Address) -> Result<Option<TransactionReceipt>, Error<M>>;
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_deactivate_token(&self, token: Address) -> TypedTransaction;
+    async fn block_token(&self, token: Address) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_block_token(
+        &self,
+        token: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_block_token(&self, token: Address) -> TypedTransaction;
     async fn enroll_token_bridge(
         &self,
         token: Address,
         fee: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
-    async fn get_registry(&self) -> Result<Address, Error<M>>;
-    async fn identify(&self) -> Result<String, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_enroll_token_bridge(
+        &self,
+        token: Address,
+        fee: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_enroll_token_bridge(&self, token: Address, fee: U256) -> TypedTransaction;
 }
 
 #[async_trait]
@@ -45,63 +68,131 @@ where
         &self,
         token: Address,
         bridge: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .add_existing_bridge(token, bridge)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref().add_existing_bridge(token, bridge),
+        )
+        .await
     }
 
-    async fn deactivate_token(
+    async fn simulate_add_existing_bridge(
         &self,
         token: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .deactivate_token(token)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+        bridge: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().add_existing_bridge(token, bridge),
+            options,
+        )
+        .await
     }
 
-    async fn block_token(&self, token: Address) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .block_token(token)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    fn build_add_existing_bridge(&self, token: Address, bridge: Address) -> TypedTransaction {
+        self.as_ref().add_existing_bridge(token, bridge).tx
+    }
+
+    async fn deactivate_token(&self, token: Address) -> Result<TransactionOutcome, Error<M>> {
+        send_call(self.as_ref(), self.as_ref().deactivate_token(token)).await
+    }
+
+    async fn simulate_deactivate_token(
+        &self,
+        token: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().deactivate_token(token),
+            options,
+        )
+        .await
+    }
+
+    fn build_deactivate_token(&self, token: Address) -> TypedTransaction {
+        self.as_ref().deactivate_token(token).tx
+    }
+
+    async fn block_token(&self, token: Address) -> Result<TransactionOutcome, Error<M>> {
+        send_call(self.as_ref(), self.as_ref().block_token(token)).await
+    }
+
+    async fn simulate_block_token(
+        &self,
+        token: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(self.as_ref(), self.as_ref().block_token(token), options).await
+    }
+
+    fn build_block_token(&self, token: Address) -> TypedTransaction {
+        self.as_ref().block_token(token).tx
     }
 
     async fn enroll_token_bridge(
         &self,
         token: Address,
         fee: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .enroll_token_bridge(token)
-            .value(fee)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref().enroll_token_bridge(token).value(fee),
+        )
+        .await
+    }
+
+    async fn simulate_enroll_token_bridge(
+        &self,
+        token: Address,
+        fee: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().enroll_token_bridge(token).value(fee),
+            options,
+        )
+        .await
     }
 
+    fn build_enroll_token_bridge(&self, token: Address, fee: U256) -> TypedTransaction {
+        self.as_ref().enroll_token_bridge(token).value(fee).tx
+    }
+}
+
+/// View methods of [`StarkgateManagerTrait`]
+#[async_trait]
+pub trait StarkgateManagerViewTrait<M: Middleware> {
+    async fn get_registry(&self) -> Result<Address, Error<M>>;
+    fn get_registry_call(&self) -> ContractCall<M, Address>;
+    async fn identify(&self) -> Result<String, Error<M>>;
+    fn identify_call(&self) -> ContractCall<M, String>;
+}
+
+#[async_trait]
+impl<T, M: Middleware> StarkgateManagerViewTrait<M> for T
+where
+    T: ContractReader<StarkgateManager<M>> + Send + Sync,
+{
     async fn get_registry(&self) -> Result<Address, Error<M>> {
-        self.as_ref()
+        self.reader()
             .get_registry()
             .call()
             .await
             .map_err(Into::into)
     }
 
+    fn get_registry_call(&self) -> ContractCall<M, Address> {
+        self.reader().get_registry()
+    }
+
     async fn identify(&self) -> Result<String, Error<M>> {
-        self.as_ref().identify().call().await.map_err(Into::into)
+        self.reader().identify().call().await.map_err(Into::into)
+    }
+
+    fn identify_call(&self) -> ContractCall<M, String> {
+        self.reader().identify()
     }
 }
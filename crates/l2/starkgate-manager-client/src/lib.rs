@@ -1,25 +1,33 @@
 use std::sync::Arc;
 
 use clients::starkgate_manager::StarkgateManagerContractClient;
-use starknet_proxy_client::deploy::{deploy_contract_behind_proxy, Error, ProxyVersion};
+use starknet_proxy_client::deploy::{
+    bundled_artifact, deploy_contract_behind_proxy, DeployOptions, Error, ProxyVersion,
+};
+use utils::errors::{ErrorContext, ResultExt};
 use utils::{LocalWalletSignerMiddleware, NO_CONSTRUCTOR_ARG};
 pub mod clients;
 pub mod interfaces;
 
-const STARKGATE_MANAGER: &str =
-    include_str!("../../../../artifacts/starkgate-contracts/StarkgateManager.json");
+bundled_artifact!(
+    STARKGATE_MANAGER,
+    "starkgate-contracts/StarkgateManager.json"
+);
 
 pub async fn deploy_starkgate_manager_behind_unsafe_proxy(
     client: Arc<LocalWalletSignerMiddleware>,
+    options: &DeployOptions,
 ) -> Result<StarkgateManagerContractClient, Error> {
     // Deploy the Starkgate Manager contract (no explicit constructor)
     let manager_contract = deploy_contract_behind_proxy(
         client.clone(),
-        STARKGATE_MANAGER,
+        &options.artifact_source().load(&STARKGATE_MANAGER)?,
         NO_CONSTRUCTOR_ARG,
         ProxyVersion::UnsafeProxy,
+        options,
     )
-    .await?;
+    .await
+    .context(ErrorContext::new().contract("StarkgateManager"))?;
 
     Ok(StarkgateManagerContractClient::new(
         manager_contract.0.address(),
@@ -30,15 +38,18 @@ pub async fn deploy_starkgate_manager_behind_unsafe_proxy(
 
 pub async fn deploy_starkgate_manager_behind_safe_proxy(
     client: Arc<LocalWalletSignerMiddleware>,
+    options: &DeployOptions,
 ) -> Result<StarkgateManagerContractClient, Error> {
     // Deploy the Starkgate Manager contract (no explicit constructor)
     let (manager_contract, manager_contract_implementation) = deploy_contract_behind_proxy(
         client.clone(),
-        STARKGATE_MANAGER,
+        &options.artifact_source().load(&STARKGATE_MANAGER)?,
         NO_CONSTRUCTOR_ARG,
         ProxyVersion::SafeProxy5_0_0,
+        options,
     )
-    .await?;
+    .await
+    .context(ErrorContext::new().contract("StarkgateManager"))?;
 
     Ok(StarkgateManagerContractClient::new(
         manager_contract.address(),
@@ -1,51 +1,74 @@
 use std::sync::Arc;
 
 use crate::interfaces::registry::StarkgateRegistry;
-use utils::{LocalWalletSignerMiddleware, StarknetContractClient};
+use starknet_proxy_client::implementation::resolve_implementation;
+use utils::errors::Error;
+use utils::registry::DeploymentEntry;
+use utils::{LocalWalletSignerMiddleware, ReadOnly, ReadWrite, StarknetContractClient};
 
+use ethers::middleware::Middleware;
 use ethers::types::Address;
 use starknet_proxy_client::clients::proxy_5_0_0::ProxySupport5_0_0;
 
 #[derive(Clone)]
-pub struct StarkgateRegistryContractClient {
-    registry: StarkgateRegistry<LocalWalletSignerMiddleware>,
-    proxy_support: ProxySupport5_0_0<LocalWalletSignerMiddleware>,
+pub struct StarkgateRegistryContractClient<M = LocalWalletSignerMiddleware> {
+    registry: StarkgateRegistry<M>,
+    proxy_support: ProxySupport5_0_0<M>,
     registry_implementation: Address,
 }
 
-impl StarkgateRegistryContractClient {
-    pub fn new(
-        address: Address,
-        client: Arc<LocalWalletSignerMiddleware>,
-        implementation_address: Address,
-    ) -> Self {
+impl<M: Middleware> StarkgateRegistryContractClient<M> {
+    pub fn new(address: Address, client: Arc<M>, implementation_address: Address) -> Self {
         Self {
             registry: StarkgateRegistry::new(address, client.clone()),
             proxy_support: ProxySupport5_0_0::new(address, client.clone()),
             registry_implementation: implementation_address,
         }
     }
+
+    /// Client of a deployment behind a proxy, its implementation being read on-chain
+    /// (see [`resolve_implementation`])
+    pub async fn attach(address: Address, client: Arc<M>) -> Result<Self, Error<M>> {
+        let implementation_address = resolve_implementation(address, client.clone()).await?;
+        Ok(Self::new(address, client, implementation_address))
+    }
+
+    /// Client of a deployment recorded in a [`utils::registry::DeploymentRegistry`]
+    pub fn from_registry_entry(entry: &DeploymentEntry, client: Arc<M>) -> Self {
+        Self::new(entry.address, client, entry.implementation)
+    }
+
+    /// Read-only client, typically over a plain `Provider`: only view methods can be called.
+    pub fn read_only(
+        address: Address,
+        client: Arc<M>,
+        implementation_address: Address,
+    ) -> ReadOnly<Self> {
+        ReadOnly::new(Self::new(address, client, implementation_address))
+    }
 }
-impl AsRef<StarkgateRegistry<LocalWalletSignerMiddleware>> for StarkgateRegistryContractClient {
-    fn as_ref(&self) -> &StarkgateRegistry<LocalWalletSignerMiddleware> {
+impl<M> AsRef<StarkgateRegistry<M>> for StarkgateRegistryContractClient<M> {
+    fn as_ref(&self) -> &StarkgateRegistry<M> {
         &self.registry
     }
 }
 
-impl AsRef<ProxySupport5_0_0<LocalWalletSignerMiddleware>> for StarkgateRegistryContractClient {
-    fn as_ref(&self) -> &ProxySupport5_0_0<LocalWalletSignerMiddleware> {
+impl<M> AsRef<ProxySupport5_0_0<M>> for StarkgateRegistryContractClient<M> {
+    fn as_ref(&self) -> &ProxySupport5_0_0<M> {
         &self.proxy_support
     }
 }
 
-impl StarknetContractClient for StarkgateRegistryContractClient {
+impl<M> ReadWrite for StarkgateRegistryContractClient<M> {}
+
+impl<M: Middleware> StarknetContractClient<M> for StarkgateRegistryContractClient<M> {
     fn address(&self) -> Address {
         self.registry.address()
     }
     fn implementation_address(&self) -> Address {
         self.registry_implementation
     }
-    fn client(&self) -> Arc<LocalWalletSignerMiddleware> {
+    fn client(&self) -> Arc<M> {
         self.registry.client()
     }
 }
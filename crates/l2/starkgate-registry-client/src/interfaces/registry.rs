@@ -1,19 +1,17 @@
 use async_trait::async_trait;
-use ethers::{
-    contract::ContractError,
-    prelude::abigen,
-    providers::Middleware,
-    types::{TransactionReceipt, H160},
-};
+use ethers::contract::ContractCall;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::{prelude::abigen, providers::Middleware, types::H160};
 
 use utils::errors::Error;
+use utils::transaction::{
+    send_call, simulate_call, SimulationOptions, SimulationOutcome, TransactionOutcome,
+};
+use utils::ContractReader;
 
 type Address = H160;
 
-abigen!(
-    StarkgateRegistry,
-    "../../../artifacts/starkgate-contracts/StarkgateRegistry.json",
-);
+abigen!(StarkgateRegistry, "abi/StarkgateRegistry.json");
 
 #[async_trait]
 pub trait StarkgateRegistryTrait<M: Middleware> {
@@ -21,11 +19,28 @@ pub trait StarkgateRegistryTrait<M: Middleware> {
         &self,
         token: Address,
         bridge: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
-    async fn block_token(&self, token: Address) -> Result<Option<TransactionReceipt>, Error<M>>;
-    async fn self_remove(&self, token: Address) -> Result<Option<TransactionReceipt>, Error<M>>;
-    async fn identify(&self) -> Result<String, Error<M>>;
-    async fn get_bridge(&self, token: Address) -> Result<Address, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_enlist_token(
+        &self,
+        token: Address,
+        bridge: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_enlist_token(&self, token: Address, bridge: Address) -> TypedTransaction;
+    async fn block_token(&self, token: Address) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_block_token(
+        &self,
+        token: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_block_token(&self, token: Address) -> TypedTransaction;
+    async fn self_remove(&self, token: Address) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_self_remove(
+        &self,
+        token: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_self_remove(&self, token: Address) -> TypedTransaction;
 }
 
 #[async_trait]
@@ -37,45 +52,92 @@ where
         &self,
         token: Address,
         bridge: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .enlist_token(token, bridge)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(self.as_ref(), self.as_ref().enlist_token(token, bridge)).await
     }
 
-    async fn block_token(&self, token: Address) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .block_token(token)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    async fn simulate_enlist_token(
+        &self,
+        token: Address,
+        bridge: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().enlist_token(token, bridge),
+            options,
+        )
+        .await
     }
 
-    async fn self_remove(&self, token: Address) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .self_remove(token)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    fn build_enlist_token(&self, token: Address, bridge: Address) -> TypedTransaction {
+        self.as_ref().enlist_token(token, bridge).tx
+    }
+
+    async fn block_token(&self, token: Address) -> Result<TransactionOutcome, Error<M>> {
+        send_call(self.as_ref(), self.as_ref().block_token(token)).await
+    }
+
+    async fn simulate_block_token(
+        &self,
+        token: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(self.as_ref(), self.as_ref().block_token(token), options).await
+    }
+
+    fn build_block_token(&self, token: Address) -> TypedTransaction {
+        self.as_ref().block_token(token).tx
+    }
+
+    async fn self_remove(&self, token: Address) -> Result<TransactionOutcome, Error<M>> {
+        send_call(self.as_ref(), self.as_ref().self_remove(token)).await
+    }
+
+    async fn simulate_self_remove(
+        &self,
+        token: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(self.as_ref(), self.as_ref().self_remove(token), options).await
+    }
+
+    fn build_self_remove(&self, token: Address) -> TypedTransaction {
+        self.as_ref().self_remove(token).tx
     }
+}
 
+/// View methods of [`StarkgateRegistryTrait`]
+#[async_trait]
+pub trait StarkgateRegistryViewTrait<M: Middleware> {
+    async fn identify(&self) -> Result<String, Error<M>>;
+    fn identify_call(&self) -> ContractCall<M, String>;
+    async fn get_bridge(&self, token: Address) -> Result<Address, Error<M>>;
+    fn get_bridge_call(&self, token: Address) -> ContractCall<M, Address>;
+}
+
+#[async_trait]
+impl<T, M: Middleware> StarkgateRegistryViewTrait<M> for T
+where
+    T: ContractReader<StarkgateRegistry<M>> + Send + Sync,
+{
     async fn identify(&self) -> Result<String, Error<M>> {
-        self.as_ref().identify().call().await.map_err(Into::into)
+        self.reader().identify().call().await.map_err(Into::into)
+    }
+
+    fn identify_call(&self) -> ContractCall<M, String> {
+        self.reader().identify()
     }
 
     async fn get_bridge(&self, token: Address) -> Result<Address, Error<M>> {
-        self.as_ref()
+        self.reader()
             .get_bridge(token)
             .call()
             .await
             .map_err(Into::into)
     }
+
+    fn get_bridge_call(&self, token: Address) -> ContractCall<M, Address> {
+        self.reader().get_bridge(token)
+    }
 }
@@ -1,26 +1,34 @@
 use std::sync::Arc;
 
 use clients::starkgate_registry::StarkgateRegistryContractClient;
-use starknet_proxy_client::deploy::{deploy_contract_behind_proxy, Error, ProxyVersion};
+use starknet_proxy_client::deploy::{
+    bundled_artifact, deploy_contract_behind_proxy, DeployOptions, Error, ProxyVersion,
+};
+use utils::errors::{ErrorContext, ResultExt};
 use utils::{LocalWalletSignerMiddleware, NO_CONSTRUCTOR_ARG};
 
 pub mod clients;
 pub mod interfaces;
 
-const STARKGATE_REGISTRY: &str =
-    include_str!("../../../../artifacts/starkgate-contracts/StarkgateRegistry.json");
+bundled_artifact!(
+    STARKGATE_REGISTRY,
+    "starkgate-contracts/StarkgateRegistry.json"
+);
 
 pub async fn deploy_starkgate_registry_behind_unsafe_proxy(
     client: Arc<LocalWalletSignerMiddleware>,
+    options: &DeployOptions,
 ) -> Result<StarkgateRegistryContractClient, Error> {
     // Deploy the Starkgate Registry contract (no explicit constructor)
     let registry_contract = deploy_contract_behind_proxy(
         client.clone(),
-        STARKGATE_REGISTRY,
+        &options.artifact_source().load(&STARKGATE_REGISTRY)?,
         NO_CONSTRUCTOR_ARG,
         ProxyVersion::UnsafeProxy,
+        options,
     )
-    .await?;
+    .await
+    .context(ErrorContext::new().contract("StarkgateRegistry"))?;
 
     Ok(StarkgateRegistryContractClient::new(
         registry_contract.0.address(),
@@ -31,15 +39,18 @@ pub async fn deploy_starkgate_registry_behind_unsafe_proxy(
 
 pub async fn deploy_starkgate_registry_behind_safe_proxy(
     client: Arc<LocalWalletSignerMiddleware>,
+    options: &DeployOptions,
 ) -> Result<StarkgateRegistryContractClient, Error> {
     // Deploy the Starkgate Registry contract (no explicit constructor)
     let (registry_contract, registry_contract_implementation) = deploy_contract_behind_proxy(
         client.clone(),
-        STARKGATE_REGISTRY,
+        &options.artifact_source().load(&STARKGATE_REGISTRY)?,
         NO_CONSTRUCTOR_ARG,
         ProxyVersion::SafeProxy5_0_0,
+        options,
     )
-    .await?;
+    .await
+    .context(ErrorContext::new().contract("StarkgateRegistry"))?;
 
     Ok(StarkgateRegistryContractClient::new(
         registry_contract.address(),
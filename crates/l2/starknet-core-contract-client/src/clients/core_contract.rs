@@ -3,28 +3,28 @@ use std::sync::Arc;
 use crate::interfaces::{
     GovernedFinalizable, Operator, StarknetCoreContract, StarknetGovernance, StarknetMessaging,
 };
+use ethers::middleware::Middleware;
 use ethers::types::Address;
 use starknet_proxy_client::clients::proxy_3_0_2::ProxySupport3_0_2;
-use utils::{LocalWalletSignerMiddleware, StarknetContractClient};
+use starknet_proxy_client::implementation::resolve_implementation;
+use utils::errors::Error;
+use utils::registry::DeploymentEntry;
+use utils::{LocalWalletSignerMiddleware, ReadOnly, ReadWrite, StarknetContractClient};
 
 /// Client to interact with a Starknet core contract running in `Sovereign` mode
 #[derive(Clone)]
-pub struct StarknetCoreContractClient {
-    core_contract: StarknetCoreContract<LocalWalletSignerMiddleware>,
-    messaging: StarknetMessaging<LocalWalletSignerMiddleware>,
-    operator: Operator<LocalWalletSignerMiddleware>,
-    proxy_support: ProxySupport3_0_2<LocalWalletSignerMiddleware>,
-    governance: StarknetGovernance<LocalWalletSignerMiddleware>,
-    governed_finalizable: GovernedFinalizable<LocalWalletSignerMiddleware>,
+pub struct StarknetCoreContractClient<M = LocalWalletSignerMiddleware> {
+    core_contract: StarknetCoreContract<M>,
+    messaging: StarknetMessaging<M>,
+    operator: Operator<M>,
+    proxy_support: ProxySupport3_0_2<M>,
+    governance: StarknetGovernance<M>,
+    governed_finalizable: GovernedFinalizable<M>,
     core_contract_implementation: Address,
 }
 
-impl StarknetCoreContractClient {
-    pub fn new(
-        address: Address,
-        client: Arc<LocalWalletSignerMiddleware>,
-        implementation_address: Address,
-    ) -> Self {
+impl<M: Middleware> StarknetCoreContractClient<M> {
+    pub fn new(address: Address, client: Arc<M>, implementation_address: Address) -> Self {
         Self {
             core_contract: StarknetCoreContract::new(address, client.clone()),
             messaging: StarknetMessaging::new(address, client.clone()),
@@ -35,47 +35,70 @@ impl StarknetCoreContractClient {
             core_contract_implementation: implementation_address,
         }
     }
+
+    /// Client of a deployment behind a proxy, its implementation being read on-chain
+    /// (see [`resolve_implementation`])
+    pub async fn attach(address: Address, client: Arc<M>) -> Result<Self, Error<M>> {
+        let implementation_address = resolve_implementation(address, client.clone()).await?;
+        Ok(Self::new(address, client, implementation_address))
+    }
+
+    /// Client of a deployment recorded in a [`utils::registry::DeploymentRegistry`]
+    pub fn from_registry_entry(entry: &DeploymentEntry, client: Arc<M>) -> Self {
+        Self::new(entry.address, client, entry.implementation)
+    }
+
+    /// Read-only client, typically over a plain `Provider`: only view methods can be called.
+    pub fn read_only(
+        address: Address,
+        client: Arc<M>,
+        implementation_address: Address,
+    ) -> ReadOnly<Self> {
+        ReadOnly::new(Self::new(address, client, implementation_address))
+    }
 }
 
-impl AsRef<StarknetCoreContract<LocalWalletSignerMiddleware>> for StarknetCoreContractClient {
-    fn as_ref(&self) -> &StarknetCoreContract<LocalWalletSignerMiddleware> {
+impl<M> AsRef<StarknetCoreContract<M>> for StarknetCoreContractClient<M> {
+    fn as_ref(&self) -> &StarknetCoreContract<M> {
         &self.core_contract
     }
 }
-impl AsRef<StarknetMessaging<LocalWalletSignerMiddleware>> for StarknetCoreContractClient {
-    fn as_ref(&self) -> &StarknetMessaging<LocalWalletSignerMiddleware> {
+impl<M> AsRef<StarknetMessaging<M>> for StarknetCoreContractClient<M> {
+    fn as_ref(&self) -> &StarknetMessaging<M> {
         &self.messaging
     }
 }
-impl AsRef<ProxySupport3_0_2<LocalWalletSignerMiddleware>> for StarknetCoreContractClient {
-    fn as_ref(&self) -> &ProxySupport3_0_2<LocalWalletSignerMiddleware> {
+impl<M> AsRef<ProxySupport3_0_2<M>> for StarknetCoreContractClient<M> {
+    fn as_ref(&self) -> &ProxySupport3_0_2<M> {
         &self.proxy_support
     }
 }
-impl AsRef<Operator<LocalWalletSignerMiddleware>> for StarknetCoreContractClient {
-    fn as_ref(&self) -> &Operator<LocalWalletSignerMiddleware> {
+impl<M> AsRef<Operator<M>> for StarknetCoreContractClient<M> {
+    fn as_ref(&self) -> &Operator<M> {
         &self.operator
     }
 }
-impl AsRef<StarknetGovernance<LocalWalletSignerMiddleware>> for StarknetCoreContractClient {
-    fn as_ref(&self) -> &StarknetGovernance<LocalWalletSignerMiddleware> {
+impl<M> AsRef<StarknetGovernance<M>> for StarknetCoreContractClient<M> {
+    fn as_ref(&self) -> &StarknetGovernance<M> {
         &self.governance
     }
 }
-impl AsRef<GovernedFinalizable<LocalWalletSignerMiddleware>> for StarknetCoreContractClient {
-    fn as_ref(&self) -> &GovernedFinalizable<LocalWalletSignerMiddleware> {
+impl<M> AsRef<GovernedFinalizable<M>> for StarknetCoreContractClient<M> {
+    fn as_ref(&self) -> &GovernedFinalizable<M> {
         &self.governed_finalizable
     }
 }
 
-impl StarknetContractClient for StarknetCoreContractClient {
+impl<M> ReadWrite for StarknetCoreContractClient<M> {}
+
+impl<M: Middleware> StarknetContractClient<M> for StarknetCoreContractClient<M> {
     fn address(&self) -> Address {
         self.core_contract.address()
     }
     fn implementation_address(&self) -> Address {
         self.core_contract_implementation
     }
-    fn client(&self) -> Arc<LocalWalletSignerMiddleware> {
+    fn client(&self) -> Arc<M> {
         self.core_contract.client()
     }
 }
@@ -1,33 +1,37 @@
 use std::sync::Arc;
 
 use ethers::abi::Address;
+use ethers::middleware::Middleware;
 use starknet_proxy_client::clients::proxy_3_0_2::ProxySupport3_0_2;
 
 use crate::interfaces::{
-    GovernedFinalizable, Operator, StarknetDevCoreContract, StarknetGovernance, StarknetMessaging,
+    GovernedFinalizable, Operator, StarknetCoreContract, StarknetDevCoreContract,
+    StarknetGovernance, StarknetMessaging,
 };
-use utils::{LocalWalletSignerMiddleware, StarknetContractClient};
+use starknet_proxy_client::implementation::resolve_implementation;
+use utils::errors::Error;
+use utils::registry::DeploymentEntry;
+use utils::{LocalWalletSignerMiddleware, ReadOnly, ReadWrite, StarknetContractClient};
 
 /// Client to interact with a Starknet core contract running in `Validity` mode
 #[derive(Clone)]
-pub struct StarknetDevCoreContractClient {
-    core_contract: StarknetDevCoreContract<LocalWalletSignerMiddleware>,
-    messaging: StarknetMessaging<LocalWalletSignerMiddleware>,
-    operator: Operator<LocalWalletSignerMiddleware>,
-    proxy_support: ProxySupport3_0_2<LocalWalletSignerMiddleware>,
-    governance: StarknetGovernance<LocalWalletSignerMiddleware>,
-    governed_finalizable: GovernedFinalizable<LocalWalletSignerMiddleware>,
+pub struct StarknetDevCoreContractClient<M = LocalWalletSignerMiddleware> {
+    core_contract: StarknetDevCoreContract<M>,
+    /// Same contract through the standard core contract ABI (subset of the dev one)
+    standard_core_contract: StarknetCoreContract<M>,
+    messaging: StarknetMessaging<M>,
+    operator: Operator<M>,
+    proxy_support: ProxySupport3_0_2<M>,
+    governance: StarknetGovernance<M>,
+    governed_finalizable: GovernedFinalizable<M>,
     core_contract_implementation: ethers::addressbook::Address,
 }
 
-impl StarknetDevCoreContractClient {
-    pub fn new(
-        address: Address,
-        client: Arc<LocalWalletSignerMiddleware>,
-        implementation_address: Address,
-    ) -> Self {
+impl<M: Middleware> StarknetDevCoreContractClient<M> {
+    pub fn new(address: Address, client: Arc<M>, implementation_address: Address) -> Self {
         Self {
             core_contract: StarknetDevCoreContract::new(address, client.clone()),
+            standard_core_contract: StarknetCoreContract::new(address, client.clone()),
             messaging: StarknetMessaging::new(address, client.clone()),
             operator: Operator::new(address, client.clone()),
             proxy_support: ProxySupport3_0_2::new(address, client.clone()),
@@ -36,47 +40,75 @@ impl StarknetDevCoreContractClient {
             core_contract_implementation: implementation_address,
         }
     }
+
+    /// Client of a deployment behind a proxy, its implementation being read on-chain
+    /// (see [`resolve_implementation`])
+    pub async fn attach(address: Address, client: Arc<M>) -> Result<Self, Error<M>> {
+        let implementation_address = resolve_implementation(address, client.clone()).await?;
+        Ok(Self::new(address, client, implementation_address))
+    }
+
+    /// Client of a deployment recorded in a [`utils::registry::DeploymentRegistry`]
+    pub fn from_registry_entry(entry: &DeploymentEntry, client: Arc<M>) -> Self {
+        Self::new(entry.address, client, entry.implementation)
+    }
+
+    /// Read-only client, typically over a plain `Provider`: only view methods can be called.
+    pub fn read_only(
+        address: Address,
+        client: Arc<M>,
+        implementation_address: Address,
+    ) -> ReadOnly<Self> {
+        ReadOnly::new(Self::new(address, client, implementation_address))
+    }
 }
 
-impl AsRef<StarknetDevCoreContract<LocalWalletSignerMiddleware>> for StarknetDevCoreContractClient {
-    fn as_ref(&self) -> &StarknetDevCoreContract<LocalWalletSignerMiddleware> {
+impl<M> AsRef<StarknetDevCoreContract<M>> for StarknetDevCoreContractClient<M> {
+    fn as_ref(&self) -> &StarknetDevCoreContract<M> {
         &self.core_contract
     }
 }
-impl AsRef<StarknetMessaging<LocalWalletSignerMiddleware>> for StarknetDevCoreContractClient {
-    fn as_ref(&self) -> &StarknetMessaging<LocalWalletSignerMiddleware> {
+impl<M> AsRef<StarknetCoreContract<M>> for StarknetDevCoreContractClient<M> {
+    fn as_ref(&self) -> &StarknetCoreContract<M> {
+        &self.standard_core_contract
+    }
+}
+impl<M> AsRef<StarknetMessaging<M>> for StarknetDevCoreContractClient<M> {
+    fn as_ref(&self) -> &StarknetMessaging<M> {
         &self.messaging
     }
 }
-impl AsRef<ProxySupport3_0_2<LocalWalletSignerMiddleware>> for StarknetDevCoreContractClient {
-    fn as_ref(&self) -> &ProxySupport3_0_2<LocalWalletSignerMiddleware> {
+impl<M> AsRef<ProxySupport3_0_2<M>> for StarknetDevCoreContractClient<M> {
+    fn as_ref(&self) -> &ProxySupport3_0_2<M> {
         &self.proxy_support
     }
 }
-impl AsRef<Operator<LocalWalletSignerMiddleware>> for StarknetDevCoreContractClient {
-    fn as_ref(&self) -> &Operator<LocalWalletSignerMiddleware> {
+impl<M> AsRef<Operator<M>> for StarknetDevCoreContractClient<M> {
+    fn as_ref(&self) -> &Operator<M> {
         &self.operator
     }
 }
-impl AsRef<StarknetGovernance<LocalWalletSignerMiddleware>> for StarknetDevCoreContractClient {
-    fn as_ref(&self) -> &StarknetGovernance<LocalWalletSignerMiddleware> {
+impl<M> AsRef<StarknetGovernance<M>> for StarknetDevCoreContractClient<M> {
+    fn as_ref(&self) -> &StarknetGovernance<M> {
         &self.governance
     }
 }
-impl AsRef<GovernedFinalizable<LocalWalletSignerMiddleware>> for StarknetDevCoreContractClient {
-    fn as_ref(&self) -> &GovernedFinalizable<LocalWalletSignerMiddleware> {
+impl<M> AsRef<GovernedFinalizable<M>> for StarknetDevCoreContractClient<M> {
+    fn as_ref(&self) -> &GovernedFinalizable<M> {
         &self.governed_finalizable
     }
 }
 
-impl StarknetContractClient for StarknetDevCoreContractClient {
+impl<M> ReadWrite for StarknetDevCoreContractClient<M> {}
+
+impl<M: Middleware> StarknetContractClient<M> for StarknetDevCoreContractClient<M> {
     fn address(&self) -> ethers::addressbook::Address {
         self.core_contract.address()
     }
     fn implementation_address(&self) -> ethers::addressbook::Address {
         self.core_contract_implementation
     }
-    fn client(&self) -> Arc<LocalWalletSignerMiddleware> {
+    fn client(&self) -> Arc<M> {
         self.core_contract.client()
     }
 }
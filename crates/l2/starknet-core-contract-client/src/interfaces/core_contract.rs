@@ -1,118 +1,174 @@
 use async_trait::async_trait;
-use ethers::contract::ContractError;
+use ethers::contract::ContractCall;
 use ethers::middleware::Middleware;
 use ethers::prelude::{abigen, Bytes};
-use ethers::types::{TransactionReceipt, I256, U256};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{I256, U256};
 
 use utils::errors::Error;
+use utils::transaction::{
+    send_call, simulate_call, SimulationOptions, SimulationOutcome, TransactionOutcome,
+};
+use utils::ContractReader;
 
-abigen!(
-    StarknetCoreContract,
-    "../../../artifacts/cairo-lang/Starknet.json",
-);
+abigen!(StarknetCoreContract, "abi/Starknet.json");
 
 #[async_trait]
 pub trait StarknetCoreContractTrait<M: Middleware> {
     async fn set_program_hash(
         &self,
         new_program_hash: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
-    async fn set_config_hash(
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_set_program_hash(
+        &self,
+        new_program_hash: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_set_program_hash(&self, new_program_hash: U256) -> TypedTransaction;
+    async fn set_config_hash(&self, new_config_hash: U256) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_set_config_hash(
         &self,
         new_config_hash: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_set_config_hash(&self, new_config_hash: U256) -> TypedTransaction;
     async fn set_message_cancellation_delay(
         &self,
         delay_in_seconds: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
-
-    async fn program_hash(&self) -> Result<U256, Error<M>>;
-    async fn config_hash(&self) -> Result<U256, Error<M>>;
-
-    async fn identify(&self) -> Result<String, Error<M>>;
-    async fn state_root(&self) -> Result<U256, Error<M>>;
-    async fn state_block_number(&self) -> Result<I256, Error<M>>;
-    async fn state_block_hash(&self) -> Result<U256, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_set_message_cancellation_delay(
+        &self,
+        delay_in_seconds: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_set_message_cancellation_delay(&self, delay_in_seconds: U256) -> TypedTransaction;
     /// Update the L1 state using calldata
     async fn update_state(
         &self,
         program_output: Vec<U256>,
         onchain_data_hash: U256,
         onchain_data_size: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_update_state(
+        &self,
+        program_output: Vec<U256>,
+        onchain_data_hash: U256,
+        onchain_data_size: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_update_state(
+        &self,
+        program_output: Vec<U256>,
+        onchain_data_hash: U256,
+        onchain_data_size: U256,
+    ) -> TypedTransaction;
     /// Update the L1 state using blob and kzg
     async fn update_state_kzg_da(
         &self,
         program_output: Vec<U256>,
         kzg_hashes: Vec<Bytes>,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_update_state_kzg_da(
+        &self,
+        program_output: Vec<U256>,
+        kzg_hashes: Vec<Bytes>,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_update_state_kzg_da(
+        &self,
+        program_output: Vec<U256>,
+        kzg_hashes: Vec<Bytes>,
+    ) -> TypedTransaction;
 }
 
-pub struct StandardCoreContract<M: Middleware>(pub StarknetCoreContract<M>);
-
 #[async_trait]
-impl<M: Middleware> StarknetCoreContractTrait<M> for StandardCoreContract<M> {
+impl<T, M: Middleware> StarknetCoreContractTrait<M> for T
+where
+    T: AsRef<StarknetCoreContract<M>> + Send + Sync,
+{
     async fn set_program_hash(
         &self,
         new_program_hash: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.0
-            .set_program_hash(new_program_hash)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref().set_program_hash(new_program_hash),
+        )
+        .await
     }
 
-    async fn set_config_hash(
+    async fn simulate_set_program_hash(
         &self,
-        new_config_hash: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.0
-            .set_config_hash(new_config_hash)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+        new_program_hash: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().set_program_hash(new_program_hash),
+            options,
+        )
+        .await
     }
 
-    async fn set_message_cancellation_delay(
-        &self,
-        delay_in_seconds: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.0
-            .set_message_cancellation_delay(delay_in_seconds)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    fn build_set_program_hash(&self, new_program_hash: U256) -> TypedTransaction {
+        self.as_ref().set_program_hash(new_program_hash).tx
     }
 
-    async fn program_hash(&self) -> Result<U256, Error<M>> {
-        self.0.program_hash().call().await.map_err(Into::into)
+    async fn set_config_hash(&self, new_config_hash: U256) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref().set_config_hash(new_config_hash),
+        )
+        .await
     }
 
-    async fn config_hash(&self) -> Result<U256, Error<M>> {
-        self.0.config_hash().call().await.map_err(Into::into)
+    async fn simulate_set_config_hash(
+        &self,
+        new_config_hash: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().set_config_hash(new_config_hash),
+            options,
+        )
+        .await
     }
 
-    async fn identify(&self) -> Result<String, Error<M>> {
-        self.0.identify().call().await.map_err(Into::into)
+    fn build_set_config_hash(&self, new_config_hash: U256) -> TypedTransaction {
+        self.as_ref().set_config_hash(new_config_hash).tx
     }
 
-    async fn state_root(&self) -> Result<U256, Error<M>> {
-        self.0.state_root().call().await.map_err(Into::into)
+    async fn set_message_cancellation_delay(
+        &self,
+        delay_in_seconds: U256,
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref()
+                .set_message_cancellation_delay(delay_in_seconds),
+        )
+        .await
     }
 
-    async fn state_block_number(&self) -> Result<I256, Error<M>> {
-        self.0.state_block_number().call().await.map_err(Into::into)
+    async fn simulate_set_message_cancellation_delay(
+        &self,
+        delay_in_seconds: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref()
+                .set_message_cancellation_delay(delay_in_seconds),
+            options,
+        )
+        .await
     }
 
-    async fn state_block_hash(&self) -> Result<U256, Error<M>> {
-        self.0.state_block_hash().call().await.map_err(Into::into)
+    fn build_set_message_cancellation_delay(&self, delay_in_seconds: U256) -> TypedTransaction {
+        self.as_ref()
+            .set_message_cancellation_delay(delay_in_seconds)
+            .tx
     }
 
     async fn update_state(
@@ -120,27 +176,161 @@ impl<M: Middleware> StarknetCoreContractTrait<M> for StandardCoreContract<M> {
         program_output: Vec<U256>,
         onchain_data_hash: U256,
         onchain_data_size: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.0
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref()
+                .update_state(program_output, onchain_data_hash, onchain_data_size),
+        )
+        .await
+    }
+
+    async fn simulate_update_state(
+        &self,
+        program_output: Vec<U256>,
+        onchain_data_hash: U256,
+        onchain_data_size: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref()
+                .update_state(program_output, onchain_data_hash, onchain_data_size),
+            options,
+        )
+        .await
+    }
+
+    fn build_update_state(
+        &self,
+        program_output: Vec<U256>,
+        onchain_data_hash: U256,
+        onchain_data_size: U256,
+    ) -> TypedTransaction {
+        self.as_ref()
             .update_state(program_output, onchain_data_hash, onchain_data_size)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+            .tx
     }
 
     async fn update_state_kzg_da(
         &self,
         program_output: Vec<U256>,
         kzg_hashes: Vec<Bytes>,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.0
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref()
+                .update_state_kzg_da(program_output, kzg_hashes),
+        )
+        .await
+    }
+
+    async fn simulate_update_state_kzg_da(
+        &self,
+        program_output: Vec<U256>,
+        kzg_hashes: Vec<Bytes>,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref()
+                .update_state_kzg_da(program_output, kzg_hashes),
+            options,
+        )
+        .await
+    }
+
+    fn build_update_state_kzg_da(
+        &self,
+        program_output: Vec<U256>,
+        kzg_hashes: Vec<Bytes>,
+    ) -> TypedTransaction {
+        self.as_ref()
             .update_state_kzg_da(program_output, kzg_hashes)
-            .send()
+            .tx
+    }
+}
+
+/// View methods of [`StarknetCoreContractTrait`]
+#[async_trait]
+pub trait StarknetCoreContractViewTrait<M: Middleware> {
+    async fn program_hash(&self) -> Result<U256, Error<M>>;
+    fn program_hash_call(&self) -> ContractCall<M, U256>;
+    async fn config_hash(&self) -> Result<U256, Error<M>>;
+    fn config_hash_call(&self) -> ContractCall<M, U256>;
+
+    async fn identify(&self) -> Result<String, Error<M>>;
+    fn identify_call(&self) -> ContractCall<M, String>;
+    async fn state_root(&self) -> Result<U256, Error<M>>;
+    fn state_root_call(&self) -> ContractCall<M, U256>;
+    async fn state_block_number(&self) -> Result<I256, Error<M>>;
+    fn state_block_number_call(&self) -> ContractCall<M, I256>;
+    async fn state_block_hash(&self) -> Result<U256, Error<M>>;
+    fn state_block_hash_call(&self) -> ContractCall<M, U256>;
+}
+
+#[async_trait]
+impl<T, M: Middleware> StarknetCoreContractViewTrait<M> for T
+where
+    T: ContractReader<StarknetCoreContract<M>> + Send + Sync,
+{
+    async fn program_hash(&self) -> Result<U256, Error<M>> {
+        self.reader()
+            .program_hash()
+            .call()
             .await
-            .map_err(Into::<ContractError<M>>::into)?
+            .map_err(Into::into)
+    }
+
+    fn program_hash_call(&self) -> ContractCall<M, U256> {
+        self.reader().program_hash()
+    }
+
+    async fn config_hash(&self) -> Result<U256, Error<M>> {
+        self.reader().config_hash().call().await.map_err(Into::into)
+    }
+
+    fn config_hash_call(&self) -> ContractCall<M, U256> {
+        self.reader().config_hash()
+    }
+
+    async fn identify(&self) -> Result<String, Error<M>> {
+        self.reader().identify().call().await.map_err(Into::into)
+    }
+
+    fn identify_call(&self) -> ContractCall<M, String> {
+        self.reader().identify()
+    }
+
+    async fn state_root(&self) -> Result<U256, Error<M>> {
+        self.reader().state_root().call().await.map_err(Into::into)
+    }
+
+    fn state_root_call(&self) -> ContractCall<M, U256> {
+        self.reader().state_root()
+    }
+
+    async fn state_block_number(&self) -> Result<I256, Error<M>> {
+        self.reader()
+            .state_block_number()
+            .call()
             .await
             .map_err(Into::into)
     }
+
+    fn state_block_number_call(&self) -> ContractCall<M, I256> {
+        self.reader().state_block_number()
+    }
+
+    async fn state_block_hash(&self) -> Result<U256, Error<M>> {
+        self.reader()
+            .state_block_hash()
+            .call()
+            .await
+            .map_err(Into::into)
+    }
+
+    fn state_block_hash_call(&self) -> ContractCall<M, U256> {
+        self.reader().state_block_hash()
+    }
 }
@@ -1,128 +1,133 @@
 use async_trait::async_trait;
-use ethers::contract::ContractError;
 use ethers::middleware::Middleware;
 use ethers::prelude::abigen;
-use ethers::types::{Bytes, TransactionReceipt, I256, U256};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{I256, U256};
 
-use crate::interfaces::core_contract::StarknetCoreContractTrait;
 use utils::errors::Error;
+use utils::transaction::{
+    send_call, simulate_call, SimulationOptions, SimulationOutcome, TransactionOutcome,
+};
 
-abigen!(
-    StarknetDevCoreContract,
-    "../../../artifacts/StarknetDevCoreContract.json",
-);
+abigen!(StarknetDevCoreContract, "abi/StarknetDevCoreContract.json");
 
-pub struct DevCoreContract<M: Middleware>(pub StarknetDevCoreContract<M>);
-
-#[allow(dead_code)]
-impl<M: Middleware> DevCoreContract<M> {
+/// Dev only methods of [`StarknetDevCoreContract`], the methods shared with the
+/// production contract are in [`crate::interfaces::StarknetCoreContractTrait`]
+#[async_trait]
+pub trait StarknetDevCoreContractTrait<M: Middleware> {
+    /// Overrides the L1 state without a proof (operator only)
     async fn update_state_override(
         &self,
         global_root: U256,
         block_number: I256,
         block_hash: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.0
-            .update_state_override(global_root, block_number, block_hash)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
-    }
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_update_state_override(
+        &self,
+        global_root: U256,
+        block_number: I256,
+        block_hash: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_update_state_override(
+        &self,
+        global_root: U256,
+        block_number: I256,
+        block_hash: U256,
+    ) -> TypedTransaction;
+    async fn set_aggregator_program_hash(
+        &self,
+        new_aggregator_program_hash: U256,
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_set_aggregator_program_hash(
+        &self,
+        new_aggregator_program_hash: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_set_aggregator_program_hash(
+        &self,
+        new_aggregator_program_hash: U256,
+    ) -> TypedTransaction;
 }
 
 #[async_trait]
-impl<M: Middleware> StarknetCoreContractTrait<M> for DevCoreContract<M> {
-    async fn set_program_hash(
+impl<T, M: Middleware> StarknetDevCoreContractTrait<M> for T
+where
+    T: AsRef<StarknetDevCoreContract<M>> + Send + Sync,
+{
+    async fn update_state_override(
         &self,
-        new_program_hash: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.0
-            .set_program_hash(new_program_hash)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+        global_root: U256,
+        block_number: I256,
+        block_hash: U256,
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref()
+                .update_state_override(global_root, block_number, block_hash),
+        )
+        .await
     }
 
-    async fn set_config_hash(
+    async fn simulate_update_state_override(
         &self,
-        new_config_hash: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.0
-            .set_config_hash(new_config_hash)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+        global_root: U256,
+        block_number: I256,
+        block_hash: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref()
+                .update_state_override(global_root, block_number, block_hash),
+            options,
+        )
+        .await
     }
 
-    async fn set_message_cancellation_delay(
+    fn build_update_state_override(
         &self,
-        delay_in_seconds: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.0
-            .set_message_cancellation_delay(delay_in_seconds)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
-    }
-
-    async fn program_hash(&self) -> Result<U256, Error<M>> {
-        self.0.program_hash().call().await.map_err(Into::into)
-    }
-
-    async fn config_hash(&self) -> Result<U256, Error<M>> {
-        self.0.config_hash().call().await.map_err(Into::into)
-    }
-
-    async fn identify(&self) -> Result<String, Error<M>> {
-        self.0.identify().call().await.map_err(Into::into)
-    }
-
-    async fn state_root(&self) -> Result<U256, Error<M>> {
-        self.0.state_root().call().await.map_err(Into::into)
-    }
-
-    async fn state_block_number(&self) -> Result<I256, Error<M>> {
-        self.0.state_block_number().call().await.map_err(Into::into)
+        global_root: U256,
+        block_number: I256,
+        block_hash: U256,
+    ) -> TypedTransaction {
+        self.as_ref()
+            .update_state_override(global_root, block_number, block_hash)
+            .tx
     }
 
-    async fn state_block_hash(&self) -> Result<U256, Error<M>> {
-        self.0.state_block_hash().call().await.map_err(Into::into)
+    async fn set_aggregator_program_hash(
+        &self,
+        new_aggregator_program_hash: U256,
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref()
+                .set_aggregator_program_hash(new_aggregator_program_hash),
+        )
+        .await
     }
 
-    async fn update_state(
+    async fn simulate_set_aggregator_program_hash(
         &self,
-        program_output: Vec<U256>,
-        onchain_data_hash: U256,
-        onchain_data_size: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.0
-            .update_state(program_output, onchain_data_hash, onchain_data_size)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+        new_aggregator_program_hash: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref()
+                .set_aggregator_program_hash(new_aggregator_program_hash),
+            options,
+        )
+        .await
     }
 
-    async fn update_state_kzg_da(
+    fn build_set_aggregator_program_hash(
         &self,
-        program_output: Vec<U256>,
-        kzg_hashes: Vec<Bytes>,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.0
-            .update_state_kzg_da(program_output, kzg_hashes)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+        new_aggregator_program_hash: U256,
+    ) -> TypedTransaction {
+        self.as_ref()
+            .set_aggregator_program_hash(new_aggregator_program_hash)
+            .tx
     }
 }
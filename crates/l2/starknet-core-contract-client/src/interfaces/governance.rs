@@ -1,12 +1,13 @@
 use async_trait::async_trait;
-use ethers::{
-    contract::ContractError,
-    prelude::abigen,
-    providers::Middleware,
-    types::{TransactionReceipt, H160},
-};
+use ethers::contract::ContractCall;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::{prelude::abigen, providers::Middleware, types::H160};
 
 use utils::errors::Error;
+use utils::transaction::{
+    send_call, simulate_call, SimulationOptions, SimulationOutcome, TransactionOutcome,
+};
+use utils::ContractReader;
 
 type Address = H160;
 
@@ -23,17 +24,38 @@ abigen!(
 
 #[async_trait]
 pub trait StarknetGovernanceTrait<M: Middleware> {
-    async fn starknet_is_governor(&self, user: Address) -> Result<bool, Error<M>>;
     async fn starknet_nominate_new_governor(
         &self,
         new_governor: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_starknet_nominate_new_governor(
+        &self,
+        new_governor: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_starknet_nominate_new_governor(&self, new_governor: Address) -> TypedTransaction;
     async fn starknet_remove_governor(
         &self,
         governor_for_removal: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
-    async fn starknet_accept_governance(&self) -> Result<Option<TransactionReceipt>, Error<M>>;
-    async fn starknet_cancel_nomination(&self) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_starknet_remove_governor(
+        &self,
+        governor_for_removal: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_starknet_remove_governor(&self, governor_for_removal: Address) -> TypedTransaction;
+    async fn starknet_accept_governance(&self) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_starknet_accept_governance(
+        &self,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_starknet_accept_governance(&self) -> TypedTransaction;
+    async fn starknet_cancel_nomination(&self) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_starknet_cancel_nomination(
+        &self,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_starknet_cancel_nomination(&self) -> TypedTransaction;
 }
 
 #[async_trait]
@@ -41,57 +63,128 @@ impl<T, M: Middleware> StarknetGovernanceTrait<M> for T
 where
     T: AsRef<StarknetGovernance<M>> + Send + Sync,
 {
-    async fn starknet_is_governor(&self, user: Address) -> Result<bool, Error<M>> {
-        self.as_ref()
-            .starknet_is_governor(user)
-            .call()
-            .await
-            .map_err(Into::into)
+    async fn starknet_nominate_new_governor(
+        &self,
+        new_governor: Address,
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref().starknet_nominate_new_governor(new_governor),
+        )
+        .await
     }
 
-    async fn starknet_nominate_new_governor(
+    async fn simulate_starknet_nominate_new_governor(
         &self,
         new_governor: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().starknet_nominate_new_governor(new_governor),
+            options,
+        )
+        .await
+    }
+
+    fn build_starknet_nominate_new_governor(&self, new_governor: Address) -> TypedTransaction {
         self.as_ref()
             .starknet_nominate_new_governor(new_governor)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+            .tx
     }
 
     async fn starknet_remove_governor(
         &self,
         governor_for_removal: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref().starknet_remove_governor(governor_for_removal),
+        )
+        .await
+    }
+
+    async fn simulate_starknet_remove_governor(
+        &self,
+        governor_for_removal: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().starknet_remove_governor(governor_for_removal),
+            options,
+        )
+        .await
+    }
+
+    fn build_starknet_remove_governor(&self, governor_for_removal: Address) -> TypedTransaction {
         self.as_ref()
             .starknet_remove_governor(governor_for_removal)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+            .tx
     }
 
-    async fn starknet_accept_governance(&self) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .starknet_accept_governance()
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    async fn starknet_accept_governance(&self) -> Result<TransactionOutcome, Error<M>> {
+        send_call(self.as_ref(), self.as_ref().starknet_accept_governance()).await
     }
 
-    async fn starknet_cancel_nomination(&self) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .starknet_cancel_nomination()
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
+    async fn simulate_starknet_accept_governance(
+        &self,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().starknet_accept_governance(),
+            options,
+        )
+        .await
+    }
+
+    fn build_starknet_accept_governance(&self) -> TypedTransaction {
+        self.as_ref().starknet_accept_governance().tx
+    }
+
+    async fn starknet_cancel_nomination(&self) -> Result<TransactionOutcome, Error<M>> {
+        send_call(self.as_ref(), self.as_ref().starknet_cancel_nomination()).await
+    }
+
+    async fn simulate_starknet_cancel_nomination(
+        &self,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().starknet_cancel_nomination(),
+            options,
+        )
+        .await
+    }
+
+    fn build_starknet_cancel_nomination(&self) -> TypedTransaction {
+        self.as_ref().starknet_cancel_nomination().tx
+    }
+}
+
+/// View methods of [`StarknetGovernanceTrait`]
+#[async_trait]
+pub trait StarknetGovernanceViewTrait<M: Middleware> {
+    async fn starknet_is_governor(&self, user: Address) -> Result<bool, Error<M>>;
+    fn starknet_is_governor_call(&self, user: Address) -> ContractCall<M, bool>;
+}
+
+#[async_trait]
+impl<T, M: Middleware> StarknetGovernanceViewTrait<M> for T
+where
+    T: ContractReader<StarknetGovernance<M>> + Send + Sync,
+{
+    async fn starknet_is_governor(&self, user: Address) -> Result<bool, Error<M>> {
+        self.reader()
+            .starknet_is_governor(user)
+            .call()
             .await
             .map_err(Into::into)
     }
+
+    fn starknet_is_governor_call(&self, user: Address) -> ContractCall<M, bool> {
+        self.reader().starknet_is_governor(user)
+    }
 }
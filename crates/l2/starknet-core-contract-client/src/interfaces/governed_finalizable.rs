@@ -1,9 +1,13 @@
 use async_trait::async_trait;
-use ethers::{
-    contract::ContractError, prelude::abigen, providers::Middleware, types::TransactionReceipt,
-};
+use ethers::contract::ContractCall;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::{prelude::abigen, providers::Middleware};
 
 use utils::errors::Error;
+use utils::transaction::{
+    send_call, simulate_call, SimulationOptions, SimulationOutcome, TransactionOutcome,
+};
+use utils::ContractReader;
 
 abigen!(
     GovernedFinalizable,
@@ -15,30 +19,56 @@ abigen!(
 
 #[async_trait]
 pub trait GovernedFinalizableTrait<M: Middleware> {
-    async fn is_finalized(&self) -> Result<bool, Error<M>>;
-    async fn finalize(&self) -> Result<Option<TransactionReceipt>, Error<M>>;
+    async fn finalize(&self) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_finalize(
+        &self,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_finalize(&self) -> TypedTransaction;
 }
 
 #[async_trait]
 impl<T, M: Middleware> GovernedFinalizableTrait<M> for T
 where
     T: AsRef<GovernedFinalizable<M>> + Send + Sync,
+{
+    async fn finalize(&self) -> Result<TransactionOutcome, Error<M>> {
+        send_call(self.as_ref(), self.as_ref().finalize()).await
+    }
+
+    async fn simulate_finalize(
+        &self,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(self.as_ref(), self.as_ref().finalize(), options).await
+    }
+
+    fn build_finalize(&self) -> TypedTransaction {
+        self.as_ref().finalize().tx
+    }
+}
+
+/// View methods of [`GovernedFinalizableTrait`]
+#[async_trait]
+pub trait GovernedFinalizableViewTrait<M: Middleware> {
+    async fn is_finalized(&self) -> Result<bool, Error<M>>;
+    fn is_finalized_call(&self) -> ContractCall<M, bool>;
+}
+
+#[async_trait]
+impl<T, M: Middleware> GovernedFinalizableViewTrait<M> for T
+where
+    T: ContractReader<GovernedFinalizable<M>> + Send + Sync,
 {
     async fn is_finalized(&self) -> Result<bool, Error<M>> {
-        self.as_ref()
+        self.reader()
             .is_finalized()
             .call()
             .await
             .map_err(Into::into)
     }
 
-    async fn finalize(&self) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .finalize()
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    fn is_finalized_call(&self) -> ContractCall<M, bool> {
+        self.reader().is_finalized()
     }
 }
@@ -1,14 +1,16 @@
 use async_trait::async_trait;
-use ethers::{
-    contract::ContractError,
-    prelude::abigen,
-    providers::Middleware,
-    types::{TransactionReceipt, U256},
-};
+use ethers::contract::ContractCall;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::{prelude::abigen, providers::Middleware, types::U256};
 
 use utils::errors::Error;
+use utils::transaction::{
+    send_call, simulate_call, SimulationOptions, SimulationOutcome, TransactionOutcome,
+};
+use utils::ContractReader;
 
-type MessageHash = [u8; 32];
+/// Hash of an L1 to L2 or L2 to L1 message, see [`crate::messages`]
+pub type MessageHash = [u8; 32];
 
 // StarknetMessaging.sol
 abigen!(
@@ -17,6 +19,7 @@ abigen!(
         function l1ToL2Messages(bytes32 msgHash) external view returns (uint256)
         function l2ToL1Messages(bytes32 msgHash) external view returns (uint256)
         function l1ToL2MessageCancellations(bytes32 msgHash) external view returns (uint256)
+        function messageCancellationDelay() public view returns (uint256)
 
         function sendMessageToL2(uint256 toAddress, uint256 selector, uint256[] calldata payload) external payable override returns (bytes32, uint256)
         function consumeMessageFromL2(uint256 fromAddress, uint256[] calldata payload) external override returns (bytes32)
@@ -28,31 +31,72 @@ abigen!(
 
 #[async_trait]
 pub trait StarknetMessagingTrait<M: Middleware> {
-    async fn l1_to_l2_messages(&self, msg_hash: MessageHash) -> Result<U256, Error<M>>;
-    async fn l2_to_l1_messages(&self, msg_hash: MessageHash) -> Result<U256, Error<M>>;
-    async fn l1_to_l2_message_cancellations(&self, msg_hash: MessageHash)
-        -> Result<U256, Error<M>>;
     async fn send_message_to_l2(
         &self,
         to_address: U256,
         selector: U256,
         payload: Vec<U256>,
         fee: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_send_message_to_l2(
+        &self,
+        to_address: U256,
+        selector: U256,
+        payload: Vec<U256>,
+        fee: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<(MessageHash, U256)>, Error<M>>;
+    fn build_send_message_to_l2(
+        &self,
+        to_address: U256,
+        selector: U256,
+        payload: Vec<U256>,
+        fee: U256,
+    ) -> TypedTransaction;
     async fn start_l1_to_l2_message_cancellation(
         &self,
         to_address: U256,
         selector: U256,
         payload: Vec<U256>,
         nonce: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_start_l1_to_l2_message_cancellation(
+        &self,
+        to_address: U256,
+        selector: U256,
+        payload: Vec<U256>,
+        nonce: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<MessageHash>, Error<M>>;
+    fn build_start_l1_to_l2_message_cancellation(
+        &self,
+        to_address: U256,
+        selector: U256,
+        payload: Vec<U256>,
+        nonce: U256,
+    ) -> TypedTransaction;
     async fn cancel_l1_to_l2_message(
         &self,
         to_address: U256,
         selector: U256,
         payload: Vec<U256>,
         nonce: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_cancel_l1_to_l2_message(
+        &self,
+        to_address: U256,
+        selector: U256,
+        payload: Vec<U256>,
+        nonce: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<MessageHash>, Error<M>>;
+    fn build_cancel_l1_to_l2_message(
+        &self,
+        to_address: U256,
+        selector: U256,
+        payload: Vec<U256>,
+        nonce: U256,
+    ) -> TypedTransaction;
 }
 
 #[async_trait]
@@ -60,48 +104,54 @@ impl<T, M: Middleware> StarknetMessagingTrait<M> for T
 where
     T: AsRef<StarknetMessaging<M>> + Send + Sync,
 {
-    async fn l1_to_l2_messages(&self, msg_hash: MessageHash) -> Result<U256, Error<M>> {
-        self.as_ref()
-            .l_1_to_l2_messages(msg_hash)
-            .call()
-            .await
-            .map_err(Into::into)
-    }
-
-    async fn l2_to_l1_messages(&self, msg_hash: MessageHash) -> Result<U256, Error<M>> {
-        self.as_ref()
-            .l_2_to_l1_messages(msg_hash)
-            .call()
-            .await
-            .map_err(Into::into)
+    async fn send_message_to_l2(
+        &self,
+        to_address: U256,
+        selector: U256,
+        payload: Vec<U256>,
+        fee: U256,
+    ) -> Result<TransactionOutcome, Error<M>> {
+        // L1 message fee must be between 0 and 1 ether
+        send_call(
+            self.as_ref(),
+            self.as_ref()
+                .send_message_to_l2(to_address, selector, payload)
+                .value(fee),
+        )
+        .await
     }
 
-    async fn l1_to_l2_message_cancellations(
+    async fn simulate_send_message_to_l2(
         &self,
-        msg_hash: MessageHash,
-    ) -> Result<U256, Error<M>> {
-        self.as_ref()
-            .l_1_to_l2_message_cancellations(msg_hash)
-            .call()
-            .await
-            .map_err(Into::into)
+        to_address: U256,
+        selector: U256,
+        payload: Vec<U256>,
+        fee: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<(MessageHash, U256)>, Error<M>> {
+        // L1 message fee must be between 0 and 1 ether
+        simulate_call(
+            self.as_ref(),
+            self.as_ref()
+                .send_message_to_l2(to_address, selector, payload)
+                .value(fee),
+            options,
+        )
+        .await
     }
 
-    async fn send_message_to_l2(
+    fn build_send_message_to_l2(
         &self,
         to_address: U256,
         selector: U256,
         payload: Vec<U256>,
         fee: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
+    ) -> TypedTransaction {
+        // L1 message fee must be between 0 and 1 ether
         self.as_ref()
             .send_message_to_l2(to_address, selector, payload)
-            .value(fee) // L1 message fee must be between 0 and 1 ether
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+            .value(fee)
+            .tx
     }
 
     async fn start_l1_to_l2_message_cancellation(
@@ -110,14 +160,42 @@ where
         selector: U256,
         payload: Vec<U256>,
         nonce: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref()
+                .start_l1_to_l2_message_cancellation(to_address, selector, payload, nonce),
+        )
+        .await
+    }
+
+    async fn simulate_start_l1_to_l2_message_cancellation(
+        &self,
+        to_address: U256,
+        selector: U256,
+        payload: Vec<U256>,
+        nonce: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<MessageHash>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref()
+                .start_l1_to_l2_message_cancellation(to_address, selector, payload, nonce),
+            options,
+        )
+        .await
+    }
+
+    fn build_start_l1_to_l2_message_cancellation(
+        &self,
+        to_address: U256,
+        selector: U256,
+        payload: Vec<U256>,
+        nonce: U256,
+    ) -> TypedTransaction {
         self.as_ref()
             .start_l1_to_l2_message_cancellation(to_address, selector, payload, nonce)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+            .tx
     }
 
     async fn cancel_l1_to_l2_message(
@@ -126,13 +204,112 @@ where
         selector: U256,
         payload: Vec<U256>,
         nonce: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref()
+                .cancel_l1_to_l2_message(to_address, selector, payload, nonce),
+        )
+        .await
+    }
+
+    async fn simulate_cancel_l1_to_l2_message(
+        &self,
+        to_address: U256,
+        selector: U256,
+        payload: Vec<U256>,
+        nonce: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<MessageHash>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref()
+                .cancel_l1_to_l2_message(to_address, selector, payload, nonce),
+            options,
+        )
+        .await
+    }
+
+    fn build_cancel_l1_to_l2_message(
+        &self,
+        to_address: U256,
+        selector: U256,
+        payload: Vec<U256>,
+        nonce: U256,
+    ) -> TypedTransaction {
         self.as_ref()
             .cancel_l1_to_l2_message(to_address, selector, payload, nonce)
-            .send()
+            .tx
+    }
+}
+
+/// View methods of [`StarknetMessagingTrait`]
+#[async_trait]
+pub trait StarknetMessagingViewTrait<M: Middleware> {
+    async fn l1_to_l2_messages(&self, msg_hash: MessageHash) -> Result<U256, Error<M>>;
+    fn l1_to_l2_messages_call(&self, msg_hash: MessageHash) -> ContractCall<M, U256>;
+    async fn l2_to_l1_messages(&self, msg_hash: MessageHash) -> Result<U256, Error<M>>;
+    fn l2_to_l1_messages_call(&self, msg_hash: MessageHash) -> ContractCall<M, U256>;
+    async fn l1_to_l2_message_cancellations(&self, msg_hash: MessageHash)
+        -> Result<U256, Error<M>>;
+    fn l1_to_l2_message_cancellations_call(&self, msg_hash: MessageHash) -> ContractCall<M, U256>;
+    async fn message_cancellation_delay(&self) -> Result<U256, Error<M>>;
+    fn message_cancellation_delay_call(&self) -> ContractCall<M, U256>;
+}
+
+#[async_trait]
+impl<T, M: Middleware> StarknetMessagingViewTrait<M> for T
+where
+    T: ContractReader<StarknetMessaging<M>> + Send + Sync,
+{
+    async fn l1_to_l2_messages(&self, msg_hash: MessageHash) -> Result<U256, Error<M>> {
+        self.reader()
+            .l_1_to_l2_messages(msg_hash)
+            .call()
             .await
-            .map_err(Into::<ContractError<M>>::into)?
+            .map_err(Into::into)
+    }
+
+    fn l1_to_l2_messages_call(&self, msg_hash: MessageHash) -> ContractCall<M, U256> {
+        self.reader().l_1_to_l2_messages(msg_hash)
+    }
+
+    async fn l2_to_l1_messages(&self, msg_hash: MessageHash) -> Result<U256, Error<M>> {
+        self.reader()
+            .l_2_to_l1_messages(msg_hash)
+            .call()
             .await
             .map_err(Into::into)
     }
+
+    fn l2_to_l1_messages_call(&self, msg_hash: MessageHash) -> ContractCall<M, U256> {
+        self.reader().l_2_to_l1_messages(msg_hash)
+    }
+
+    async fn l1_to_l2_message_cancellations(
+        &self,
+        msg_hash: MessageHash,
+    ) -> Result<U256, Error<M>> {
+        self.reader()
+            .l_1_to_l2_message_cancellations(msg_hash)
+            .call()
+            .await
+            .map_err(Into::into)
+    }
+
+    fn l1_to_l2_message_cancellations_call(&self, msg_hash: MessageHash) -> ContractCall<M, U256> {
+        self.reader().l_1_to_l2_message_cancellations(msg_hash)
+    }
+
+    async fn message_cancellation_delay(&self) -> Result<U256, Error<M>> {
+        self.reader()
+            .message_cancellation_delay()
+            .call()
+            .await
+            .map_err(Into::into)
+    }
+
+    fn message_cancellation_delay_call(&self) -> ContractCall<M, U256> {
+        self.reader().message_cancellation_delay()
+    }
 }
@@ -6,14 +6,23 @@ mod messaging;
 mod messaging_events;
 mod operator;
 
-pub use core_contract::{StarknetCoreContract, StarknetCoreContractTrait};
-pub use dev_core_contract::StarknetDevCoreContract;
-pub use governance::{StarknetGovernance, StarknetGovernanceTrait};
-pub use governed_finalizable::{GovernedFinalizable, GovernedFinalizableTrait};
-pub use messaging::{StarknetMessaging, StarknetMessagingTrait};
+pub use core_contract::{
+    StarknetCoreContract, StarknetCoreContractTrait, StarknetCoreContractViewTrait,
+    STARKNETCORECONTRACT_ABI,
+};
+pub use dev_core_contract::{
+    StarknetDevCoreContract, StarknetDevCoreContractTrait, STARKNETDEVCORECONTRACT_ABI,
+};
+pub use governance::{StarknetGovernance, StarknetGovernanceTrait, StarknetGovernanceViewTrait};
+pub use governed_finalizable::{
+    GovernedFinalizable, GovernedFinalizableTrait, GovernedFinalizableViewTrait,
+};
+pub use messaging::{
+    MessageHash, StarknetMessaging, StarknetMessagingTrait, StarknetMessagingViewTrait,
+};
 pub use messaging_events::{
     ConsumedMessageToL1Filter, ConsumedMessageToL2Filter, LogMessageToL1Filter,
     LogMessageToL2Filter, MessageToL2CanceledFilter, MessageToL2CancellationStartedFilter,
     StarknetMessagingEvents,
 };
-pub use operator::{Operator, OperatorTrait};
+pub use operator::{Operator, OperatorTrait, OperatorViewTrait};
@@ -1,12 +1,13 @@
 use async_trait::async_trait;
-use ethers::{
-    contract::ContractError,
-    prelude::abigen,
-    providers::Middleware,
-    types::{Address, TransactionReceipt},
-};
+use ethers::contract::ContractCall;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::{prelude::abigen, providers::Middleware, types::Address};
 
 use utils::errors::Error;
+use utils::transaction::{
+    send_call, simulate_call, SimulationOptions, SimulationOutcome, TransactionOutcome,
+};
+use utils::ContractReader;
 
 abigen!(
     Operator,
@@ -23,12 +24,23 @@ pub trait OperatorTrait<M: Middleware> {
     async fn register_operator(
         &self,
         new_operator: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_register_operator(
+        &self,
+        new_operator: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_register_operator(&self, new_operator: Address) -> TypedTransaction;
     async fn unregister_operator(
         &self,
         removed_operator: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
-    async fn is_operator(&self, user: Address) -> Result<bool, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_unregister_operator(
+        &self,
+        removed_operator: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_unregister_operator(&self, removed_operator: Address) -> TypedTransaction;
 }
 #[async_trait]
 impl<T, M: Middleware> OperatorTrait<M> for T
@@ -38,32 +50,77 @@ where
     async fn register_operator(
         &self,
         new_operator: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .register_operator(new_operator)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(self.as_ref(), self.as_ref().register_operator(new_operator)).await
     }
+
+    async fn simulate_register_operator(
+        &self,
+        new_operator: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().register_operator(new_operator),
+            options,
+        )
+        .await
+    }
+
+    fn build_register_operator(&self, new_operator: Address) -> TypedTransaction {
+        self.as_ref().register_operator(new_operator).tx
+    }
+
     async fn unregister_operator(
         &self,
         removed_operator: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .unregister_operator(removed_operator)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref().unregister_operator(removed_operator),
+        )
+        .await
+    }
+
+    async fn simulate_unregister_operator(
+        &self,
+        removed_operator: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().unregister_operator(removed_operator),
+            options,
+        )
+        .await
     }
+
+    fn build_unregister_operator(&self, removed_operator: Address) -> TypedTransaction {
+        self.as_ref().unregister_operator(removed_operator).tx
+    }
+}
+
+/// View methods of [`OperatorTrait`]
+#[async_trait]
+pub trait OperatorViewTrait<M: Middleware> {
+    async fn is_operator(&self, user: Address) -> Result<bool, Error<M>>;
+    fn is_operator_call(&self, user: Address) -> ContractCall<M, bool>;
+}
+
+#[async_trait]
+impl<T, M: Middleware> OperatorViewTrait<M> for T
+where
+    T: ContractReader<Operator<M>> + Send + Sync,
+{
     async fn is_operator(&self, user: Address) -> Result<bool, Error<M>> {
-        self.as_ref()
+        self.reader()
             .is_operator(user)
             .call()
             .await
             .map_err(Into::into)
     }
+
+    fn is_operator_call(&self, user: Address) -> ContractCall<M, bool> {
+        self.reader().is_operator(user)
+    }
 }
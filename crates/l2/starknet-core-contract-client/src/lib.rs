@@ -1,15 +1,28 @@
+use std::fmt;
 use std::sync::Arc;
 
 use crate::clients::{StarknetCoreContractClient, StarknetDevCoreContractClient};
-use starknet_proxy_client::deploy::{deploy_contract_behind_proxy, Error, ProxyVersion};
-use utils::{LocalWalletSignerMiddleware, NO_CONSTRUCTOR_ARG};
+use crate::interfaces::{OperatorTrait, StarknetCoreContractTrait, StarknetGovernanceTrait};
+use ethers::types::{Address, Bytes, U256};
+use starknet_proxy_client::clients::proxy_5_0_0::ProxySupport5_0_0;
+use starknet_proxy_client::deploy::{
+    bundled_artifact, deploy_contract_behind_proxy, DeployOptions, Error, ProxyVersion,
+};
+use starknet_proxy_client::interfaces::proxy::{
+    CoreContractInitData, CoreContractState, ProxyInitializeData, ProxySupport3_0_2Trait,
+};
+use utils::errors::{ErrorContext, ResultExt};
+use utils::transaction::{send_call, TransactionOutcome};
+use utils::{LocalWalletSignerMiddleware, StarknetContractClient, NO_CONSTRUCTOR_ARG};
 
 pub mod clients;
 pub mod interfaces;
+pub mod messages;
+pub mod output;
+pub mod preflight;
 
-const STARKNET_CORE_CONTRACT: &str = include_str!("../../../../artifacts/cairo-lang/Starknet.json");
-const STARKNET_DEV_CORE_CONTRACT: &str =
-    include_str!("../../../../artifacts/StarknetDevCoreContract.json");
+bundled_artifact!(STARKNET_CORE_CONTRACT, "cairo-lang/Starknet.json");
+bundled_artifact!(STARKNET_DEV_CORE_CONTRACT, "StarknetDevCoreContract.json");
 
 pub enum CoreContractType {
     // custom contract written for testing (contains override function)
@@ -23,20 +36,165 @@ pub enum CoreContractClientType {
     Production(StarknetCoreContractClient),
 }
 
+impl CoreContractClientType {
+    /// Address of the proxy the core contract is deployed behind
+    pub fn address(&self) -> Address {
+        match self {
+            Self::Dev(core_contract) => core_contract.address(),
+            Self::Production(core_contract) => core_contract.address(),
+        }
+    }
+
+    pub fn implementation_address(&self) -> Address {
+        match self {
+            Self::Dev(core_contract) => core_contract.implementation_address(),
+            Self::Production(core_contract) => core_contract.implementation_address(),
+        }
+    }
+}
+
+impl fmt::Debug for CoreContractClientType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Dev(_) => "Dev",
+            Self::Production(_) => "Production",
+        };
+        f.debug_struct(name)
+            .field("address", &self.address())
+            .field("implementation_address", &self.implementation_address())
+            .finish()
+    }
+}
+
+/// Error of [`deploy_and_initialize_starknet_core_contract`]
+#[derive(Debug, thiserror::Error)]
+pub enum CoreContractDeployError {
+    #[error("Invalid core contract config: {0}")]
+    InvalidConfig(&'static str),
+    /// The core contract could not be deployed
+    #[error(transparent)]
+    Deploy(#[from] Error),
+    /// The core contract is deployed but one of the transactions configuring it failed,
+    /// its client is returned to complete the configuration
+    #[error(
+        "Core contract deployed at {:?} but not configured: {source}",
+        .core_contract.address()
+    )]
+    Configure {
+        core_contract: Box<CoreContractClientType>,
+        #[source]
+        source: Error,
+    },
+}
+
+/// Initial configuration of a core contract, see [`deploy_and_initialize_starknet_core_contract`]
+#[derive(Debug, Clone, Default)]
+pub struct CoreContractConfig {
+    program_hash: U256,
+    aggregator_program_hash: U256,
+    config_hash: U256,
+    verifier_address: Address,
+    initial_state: CoreContractState,
+    operators: Vec<Address>,
+    message_cancellation_delay: Option<U256>,
+    governor: Option<Address>,
+}
+
+impl CoreContractConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hash of the Starknet OS program, must not be zero
+    pub fn program_hash(mut self, program_hash: impl Into<U256>) -> Self {
+        self.program_hash = program_hash.into();
+        self
+    }
+
+    pub fn aggregator_program_hash(mut self, aggregator_program_hash: impl Into<U256>) -> Self {
+        self.aggregator_program_hash = aggregator_program_hash.into();
+        self
+    }
+
+    pub fn config_hash(mut self, config_hash: impl Into<U256>) -> Self {
+        self.config_hash = config_hash.into();
+        self
+    }
+
+    /// Fact registry checking the state update proofs
+    pub fn verifier_address(mut self, verifier_address: Address) -> Self {
+        self.verifier_address = verifier_address;
+        self
+    }
+
+    pub fn initial_state(mut self, initial_state: CoreContractState) -> Self {
+        self.initial_state = initial_state;
+        self
+    }
+
+    /// Registers `operator`, allowed to update the state
+    pub fn operator(mut self, operator: Address) -> Self {
+        self.operators.push(operator);
+        self
+    }
+
+    /// Delay before an L1 to L2 message can be cancelled, 5 days if not set
+    pub fn message_cancellation_delay(mut self, delay_in_seconds: impl Into<U256>) -> Self {
+        self.message_cancellation_delay = Some(delay_in_seconds.into());
+        self
+    }
+
+    /// Nominates `governor` as Starknet governor, and as proxy governor on a safe proxy 3.0.2:
+    /// the nominations have to be accepted by `governor` (`starknet_accept_governance`,
+    /// `proxy_accept_governance`). On a safe proxy 5.0.0 `governor` is granted the governance
+    /// admin and upgrade governor roles right away. The deployer keeps its governance roles until
+    /// `governor` removes them.
+    pub fn nominate_governor(mut self, governor: Address) -> Self {
+        self.governor = Some(governor);
+        self
+    }
+
+    /// Checks the config before deploying a core contract with it
+    pub fn validate(&self) -> Result<(), CoreContractDeployError> {
+        if self.program_hash.is_zero() {
+            return Err(CoreContractDeployError::InvalidConfig(
+                "the program hash must not be zero",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Data the core contract is initialized with through its proxy
+    pub fn init_data(&self) -> CoreContractInitData {
+        CoreContractInitData {
+            program_hash: self.program_hash,
+            aggregate_program_hash: self.aggregator_program_hash,
+            verifier_address: self.verifier_address,
+            config_hash: self.config_hash,
+            initial_state: self.initial_state.clone(),
+        }
+    }
+}
+
 pub async fn deploy_starknet_core_contract_behind_proxy(
     client: Arc<LocalWalletSignerMiddleware>,
     proxy_type: ProxyVersion,
     core_contract_type: CoreContractType,
+    options: &DeployOptions,
 ) -> Result<CoreContractClientType, Error> {
     match core_contract_type {
         CoreContractType::Dev => {
             let core_contract = deploy_contract_behind_proxy(
                 client.clone(),
-                STARKNET_DEV_CORE_CONTRACT,
+                &options
+                    .artifact_source()
+                    .load(&STARKNET_DEV_CORE_CONTRACT)?,
                 NO_CONSTRUCTOR_ARG,
                 proxy_type,
+                options,
             )
-            .await?;
+            .await
+            .context(ErrorContext::new().contract("StarknetDevCoreContract"))?;
             Ok(CoreContractClientType::Dev(
                 StarknetDevCoreContractClient::new(
                     core_contract.0.address(),
@@ -48,11 +206,13 @@ pub async fn deploy_starknet_core_contract_behind_proxy(
         CoreContractType::Production => {
             let core_contract = deploy_contract_behind_proxy(
                 client.clone(),
-                STARKNET_CORE_CONTRACT,
+                &options.artifact_source().load(&STARKNET_CORE_CONTRACT)?,
                 NO_CONSTRUCTOR_ARG,
                 proxy_type,
+                options,
             )
-            .await?;
+            .await
+            .context(ErrorContext::new().contract("Starknet"))?;
             Ok(CoreContractClientType::Production(
                 StarknetCoreContractClient::new(
                     core_contract.0.address(),
@@ -63,3 +223,293 @@ pub async fn deploy_starknet_core_contract_behind_proxy(
         }
     }
 }
+
+/// Deploys the core contract behind a proxy like [`deploy_starknet_core_contract_behind_proxy`],
+/// initializes it and applies the rest of `config`, returning a client of the ready to use
+/// contract. The deployer is the Starknet governor while configuring it, every transaction being
+/// awaited in turn: the first one failing or reverting aborts the configuration with
+/// [`CoreContractDeployError::Configure`], which holds the client of the deployed contract.
+pub async fn deploy_and_initialize_starknet_core_contract(
+    client: Arc<LocalWalletSignerMiddleware>,
+    proxy_type: ProxyVersion,
+    core_contract_type: CoreContractType,
+    config: &CoreContractConfig,
+    options: &DeployOptions,
+) -> Result<CoreContractClientType, CoreContractDeployError> {
+    config.validate()?;
+    let core_contract = deploy_starknet_core_contract_behind_proxy(
+        client.clone(),
+        proxy_type,
+        core_contract_type,
+        options,
+    )
+    .await?;
+
+    let result = match &core_contract {
+        CoreContractClientType::Dev(core_contract) => {
+            initialize_core_contract(core_contract, "StarknetDevCoreContract", proxy_type, config)
+                .await
+        }
+        CoreContractClientType::Production(core_contract) => {
+            initialize_core_contract(core_contract, "Starknet", proxy_type, config).await
+        }
+    };
+    match result {
+        Ok(()) => Ok(core_contract),
+        Err(source) => Err(CoreContractDeployError::Configure {
+            core_contract: Box::new(core_contract),
+            source,
+        }),
+    }
+}
+
+async fn initialize_core_contract<C>(
+    core_contract: &C,
+    contract_name: &str,
+    proxy_type: ProxyVersion,
+    config: &CoreContractConfig,
+) -> Result<(), Error>
+where
+    C: StarknetContractClient
+        + ProxySupport3_0_2Trait<LocalWalletSignerMiddleware>
+        + StarknetCoreContractTrait<LocalWalletSignerMiddleware>
+        + OperatorTrait<LocalWalletSignerMiddleware>
+        + StarknetGovernanceTrait<LocalWalletSignerMiddleware>
+        + Sync,
+{
+    let address = core_contract.address();
+    let implementation = core_contract.implementation_address();
+    let deployer = core_contract.client().address();
+    let ensure_success = |outcome: TransactionOutcome, method: &str| {
+        outcome.into_receipt().map(drop).context(
+            ErrorContext::new()
+                .contract(contract_name)
+                .method(method)
+                .address(address),
+        )
+    };
+
+    let init_data: Bytes = ProxyInitializeData::<0> {
+        sub_contract_addresses: [],
+        eic_address: Address::zero(),
+        init_data: config.init_data(),
+    }
+    .into();
+    match proxy_type {
+        // The unsafe proxy forwards every call, including `initialize`, to the implementation
+        ProxyVersion::UnsafeProxy => {
+            ensure_success(core_contract.initialize(init_data).await?, "initialize")?;
+        }
+        // Safe proxies initialize the implementation when upgrading to it
+        ProxyVersion::SafeProxy3_0_2 | ProxyVersion::SafeProxy5_0_0 => {
+            if proxy_type == ProxyVersion::SafeProxy5_0_0 {
+                let proxy = ProxySupport5_0_0::new(address, core_contract.client());
+                ensure_success(
+                    send_call(&proxy, proxy.register_upgrade_governor(deployer)).await?,
+                    "registerUpgradeGovernor",
+                )?;
+            }
+            ensure_success(
+                core_contract
+                    .add_implementation(init_data.clone(), implementation, false)
+                    .await?,
+                "addImplementation",
+            )?;
+            ensure_success(
+                core_contract
+                    .upgrade_to(init_data, implementation, false)
+                    .await?,
+                "upgradeTo",
+            )?;
+        }
+    }
+
+    for operator in &config.operators {
+        ensure_success(
+            core_contract.register_operator(*operator).await?,
+            "registerOperator",
+        )?;
+    }
+    if let Some(delay) = config.message_cancellation_delay {
+        ensure_success(
+            core_contract.set_message_cancellation_delay(delay).await?,
+            "setMessageCancellationDelay",
+        )?;
+    }
+    if let Some(governor) = config.governor.filter(|governor| *governor != deployer) {
+        ensure_success(
+            core_contract
+                .starknet_nominate_new_governor(governor)
+                .await?,
+            "starknetNominateNewGovernor",
+        )?;
+        match proxy_type {
+            ProxyVersion::UnsafeProxy => {}
+            ProxyVersion::SafeProxy3_0_2 => {
+                ensure_success(
+                    core_contract.proxy_nominate_new_governor(governor).await?,
+                    "proxyNominateNewGovernor",
+                )?;
+            }
+            ProxyVersion::SafeProxy5_0_0 => {
+                let proxy = ProxySupport5_0_0::new(address, core_contract.client());
+                ensure_success(
+                    send_call(&proxy, proxy.register_governance_admin(governor)).await?,
+                    "registerGovernanceAdmin",
+                )?;
+                ensure_success(
+                    send_call(&proxy, proxy.register_upgrade_governor(governor)).await?,
+                    "registerUpgradeGovernor",
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interfaces::{
+        OperatorViewTrait, StarknetCoreContractViewTrait, StarknetDevCoreContractTrait,
+        StarknetGovernanceViewTrait, StarknetMessagingViewTrait,
+    };
+    use ethereum_instance::AnvilBuilder;
+    use ethers::types::I256;
+    use starknet_proxy_client::clients::proxy_3_0_2::ProxySupport3_0_2;
+    use utils::transaction::{SimulationOptions, SimulationOutcome};
+
+    #[test]
+    fn test_validate_config() {
+        assert!(matches!(
+            CoreContractConfig::new().validate(),
+            Err(CoreContractDeployError::InvalidConfig(_))
+        ));
+        assert!(CoreContractConfig::new().program_hash(1).validate().is_ok());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires anvil"]
+    async fn test_deploy_and_initialize_core_contract() {
+        let ethereum = AnvilBuilder::new().spawn().unwrap();
+        let client = ethereum.signer();
+        let governor_client = ethereum.signer_at(1).unwrap();
+        let (deployer, governor) = (client.address(), governor_client.address());
+        let operator = Address::repeat_byte(0x11);
+        // Anvil mines a block per transaction, only wait for inclusion
+        let options = DeployOptions::default().confirmations(1);
+        let config = CoreContractConfig::new()
+            .program_hash(1)
+            .config_hash(2)
+            .operator(operator)
+            .message_cancellation_delay(60)
+            .nominate_governor(governor);
+
+        for proxy_type in [
+            ProxyVersion::UnsafeProxy,
+            ProxyVersion::SafeProxy3_0_2,
+            ProxyVersion::SafeProxy5_0_0,
+        ] {
+            let CoreContractClientType::Dev(core_contract) =
+                deploy_and_initialize_starknet_core_contract(
+                    client.clone(),
+                    proxy_type,
+                    CoreContractType::Dev,
+                    &config,
+                    &options,
+                )
+                .await
+                .unwrap()
+            else {
+                panic!("expected a dev core contract");
+            };
+            assert_eq!(core_contract.program_hash().await.unwrap(), 1.into());
+            assert_eq!(core_contract.config_hash().await.unwrap(), 2.into());
+            assert!(core_contract.is_operator(operator).await.unwrap());
+            assert_eq!(
+                core_contract.message_cancellation_delay().await.unwrap(),
+                60.into()
+            );
+
+            let governed = StarknetDevCoreContractClient::new(
+                core_contract.address(),
+                governor_client.clone(),
+                core_contract.implementation_address(),
+            );
+            assert!(!core_contract.starknet_is_governor(governor).await.unwrap());
+            assert!(governed
+                .starknet_accept_governance()
+                .await
+                .unwrap()
+                .is_success());
+            assert!(core_contract.starknet_is_governor(governor).await.unwrap());
+            assert!(core_contract.starknet_is_governor(deployer).await.unwrap());
+
+            match proxy_type {
+                ProxyVersion::UnsafeProxy => {}
+                ProxyVersion::SafeProxy3_0_2 => {
+                    assert!(governed
+                        .proxy_accept_governance()
+                        .await
+                        .unwrap()
+                        .is_success());
+                    let proxy = ProxySupport3_0_2::new(core_contract.address(), client.clone());
+                    assert!(proxy.proxy_is_governor(governor).call().await.unwrap());
+                }
+                ProxyVersion::SafeProxy5_0_0 => {
+                    let proxy = ProxySupport5_0_0::new(core_contract.address(), client.clone());
+                    assert!(proxy.is_governance_admin(governor).call().await.unwrap());
+                    assert!(proxy.is_upgrade_governor(governor).call().await.unwrap());
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires anvil"]
+    async fn test_simulate_operator_call() {
+        let ethereum = AnvilBuilder::new().spawn().unwrap();
+        let client = ethereum.signer();
+        let operator = client.address();
+        // Anvil mines a block per transaction, only wait for inclusion
+        let options = DeployOptions::default().confirmations(1);
+        let config = CoreContractConfig::new().program_hash(1).operator(operator);
+
+        let CoreContractClientType::Dev(core_contract) =
+            deploy_and_initialize_starknet_core_contract(
+                client,
+                ProxyVersion::UnsafeProxy,
+                CoreContractType::Dev,
+                &config,
+                &options,
+            )
+            .await
+            .unwrap()
+        else {
+            panic!("expected a dev core contract");
+        };
+
+        // Sent from the signer of the client, a registered operator
+        let outcome = core_contract
+            .simulate_update_state_override(
+                1.into(),
+                I256::zero(),
+                1.into(),
+                &SimulationOptions::new(),
+            )
+            .await
+            .unwrap();
+        assert!(outcome.is_success());
+
+        let outcome = core_contract
+            .simulate_update_state_override(
+                1.into(),
+                I256::zero(),
+                1.into(),
+                &SimulationOptions::new().from(Address::repeat_byte(0x11)),
+            )
+            .await
+            .unwrap();
+        assert!(matches!(outcome, SimulationOutcome::Reverted(_)));
+    }
+}
@@ -0,0 +1,372 @@
+//! Hashes of the L1 to L2 and L2 to L1 messages, as computed by `StarknetMessaging.sol`
+//! (`getL1ToL2MsgHash` / `getL2ToL1MsgHash`), and status of a message on L1.
+
+use async_trait::async_trait;
+use ethers::contract::{ContractError, EthLogDecode, Event};
+use ethers::middleware::Middleware;
+use ethers::types::{Address, U256};
+use ethers::utils::keccak256;
+use std::sync::Arc;
+use utils::errors::Error;
+use utils::registry::DeploymentEntry;
+use utils::StarknetContractClient;
+
+use crate::interfaces::{MessageHash, StarknetMessagingEvents, StarknetMessagingViewTrait};
+use crate::output::{address_to_word, MessageToL1, MessageToL2};
+
+/// Hash of an L1 to L2 message, key of `l1_to_l2_messages` and
+/// `l1_to_l2_message_cancellations`
+pub fn l1_to_l2_message_hash(
+    from_address: Address,
+    to_address: U256,
+    selector: U256,
+    payload: &[U256],
+    nonce: U256,
+) -> MessageHash {
+    let prefix = [
+        address_to_word(from_address),
+        to_address,
+        nonce,
+        selector,
+        payload.len().into(),
+    ];
+    hash_words(prefix.iter().chain(payload))
+}
+
+/// Hash of an L2 to L1 message, key of `l2_to_l1_messages`
+pub fn l2_to_l1_message_hash(
+    from_address: U256,
+    to_address: Address,
+    payload: &[U256],
+) -> MessageHash {
+    let prefix = [
+        from_address,
+        address_to_word(to_address),
+        payload.len().into(),
+    ];
+    hash_words(prefix.iter().chain(payload))
+}
+
+fn hash_words<'a>(words: impl Iterator<Item = &'a U256>) -> MessageHash {
+    let mut bytes = Vec::new();
+    for word in words {
+        bytes.extend_from_slice(&<[u8; 32]>::from(*word));
+    }
+    keccak256(bytes)
+}
+
+impl MessageToL2 {
+    pub fn hash(&self) -> MessageHash {
+        l1_to_l2_message_hash(
+            self.from_address,
+            self.to_address,
+            self.selector,
+            &self.payload,
+            self.nonce,
+        )
+    }
+}
+
+impl MessageToL1 {
+    pub fn hash(&self) -> MessageHash {
+        l2_to_l1_message_hash(self.from_address, self.to_address, &self.payload)
+    }
+}
+
+/// Status of a message on L1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageStatus {
+    /// Waiting to be consumed: by a state update for an L1 to L2 message, with
+    /// `consumeMessageFromL2` for an L2 to L1 message
+    Pending,
+    /// Consumed: by a state update for an L1 to L2 message (even if its cancellation was
+    /// requested), with `consumeMessageFromL2` for an L2 to L1 message
+    Consumed,
+    /// Cancellation of the L1 to L2 message started, it can be cancelled from `unlock_time`
+    /// (timestamp in seconds)
+    CancellationRequested { unlock_time: U256 },
+    /// L1 to L2 message cancelled
+    Cancelled,
+    /// Never sent, or for an L2 to L1 message not part of a state update yet
+    NotSent,
+}
+
+/// Number of blocks queried per `eth_getLogs` request by default
+const DEFAULT_LOG_BLOCK_RANGE: u64 = 2_000;
+
+/// Blocks the events of a message are looked up in by [`MessageStatusTrait`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageStatusOptions {
+    from_block: u64,
+    block_range: u64,
+}
+
+impl MessageStatusOptions {
+    /// Events looked up from `from_block` to the latest block, `from_block` being e.g. the block
+    /// the L1 to L2 message was sent in or the deployment block of the core contract
+    pub fn from_block(from_block: u64) -> Self {
+        Self {
+            from_block,
+            block_range: DEFAULT_LOG_BLOCK_RANGE,
+        }
+    }
+
+    /// Events looked up from the deployment block of the core contract recorded in `entry`,
+    /// from the genesis block if it is unknown
+    pub fn from_deployment(entry: &DeploymentEntry) -> Self {
+        Self::from_block(entry.block_number.unwrap_or_default())
+    }
+
+    /// Number of blocks queried per `eth_getLogs` request (2000 by default), to stay within the
+    /// limits of the RPC endpoint
+    pub fn block_range(mut self, block_range: u64) -> Self {
+        self.block_range = block_range.max(1);
+        self
+    }
+}
+
+/// Statuses of the messages. The core contract storage does not tell a consumed message from a
+/// never sent one, nor from a cancelled one if its cancellation was requested: when the message
+/// is neither pending nor being cancelled, its `ConsumedMessageToL2` / `ConsumedMessageToL1`
+/// event is looked up in the blocks of `options`.
+#[async_trait]
+pub trait MessageStatusTrait<M: Middleware> {
+    async fn l1_to_l2_message_status(
+        &self,
+        message: &MessageToL2,
+        options: &MessageStatusOptions,
+    ) -> Result<MessageStatus, Error<M>>;
+    async fn l2_to_l1_message_status(
+        &self,
+        message: &MessageToL1,
+        options: &MessageStatusOptions,
+    ) -> Result<MessageStatus, Error<M>>;
+}
+
+#[async_trait]
+impl<T, M: Middleware> MessageStatusTrait<M> for T
+where
+    T: StarknetMessagingViewTrait<M> + StarknetContractClient<M> + Sync,
+{
+    async fn l1_to_l2_message_status(
+        &self,
+        message: &MessageToL2,
+        options: &MessageStatusOptions,
+    ) -> Result<MessageStatus, Error<M>> {
+        let msg_hash = message.hash();
+        // Fee of the message plus one until it is consumed or cancelled
+        let fee_plus_one = self.l1_to_l2_messages(msg_hash).await?;
+        let cancellation_time = self.l1_to_l2_message_cancellations(msg_hash).await?;
+
+        let status = match (fee_plus_one.is_zero(), cancellation_time.is_zero()) {
+            (false, true) => MessageStatus::Pending,
+            (false, false) => MessageStatus::CancellationRequested {
+                unlock_time: cancellation_time
+                    .saturating_add(self.message_cancellation_delay().await?),
+            },
+            // Cleared by a state update or a cancellation, which both keep the cancellation
+            // time, or never sent
+            (true, _) => {
+                // The nonce is not indexed, messages sharing the sender, the recipient and the
+                // selector are told apart by their nonce and payload
+                let event = messaging_events(self)
+                    .consumed_message_to_l2_filter()
+                    .topic1(message.from_address)
+                    .topic2(message.to_address)
+                    .topic3(message.selector);
+                let consumed = find_event(&self.client(), event, options, |event| {
+                    event.nonce == message.nonce && event.payload == message.payload
+                })
+                .await?;
+                if consumed {
+                    MessageStatus::Consumed
+                } else if !cancellation_time.is_zero() {
+                    MessageStatus::Cancelled
+                } else {
+                    MessageStatus::NotSent
+                }
+            }
+        };
+        Ok(status)
+    }
+
+    async fn l2_to_l1_message_status(
+        &self,
+        message: &MessageToL1,
+        options: &MessageStatusOptions,
+    ) -> Result<MessageStatus, Error<M>> {
+        // Number of copies of the message waiting to be consumed
+        let count = self.l2_to_l1_messages(message.hash()).await?;
+        if !count.is_zero() {
+            return Ok(MessageStatus::Pending);
+        }
+
+        let event = messaging_events(self)
+            .consumed_message_to_l1_filter()
+            .topic1(message.from_address)
+            .topic2(message.to_address);
+        let consumed = find_event(&self.client(), event, options, |event| {
+            event.payload == message.payload
+        })
+        .await?;
+        if consumed {
+            Ok(MessageStatus::Consumed)
+        } else {
+            Ok(MessageStatus::NotSent)
+        }
+    }
+}
+
+/// Events of the messaging contract, emitted by the proxy the core contract is deployed behind
+fn messaging_events<M: Middleware>(
+    core_contract: &impl StarknetContractClient<M>,
+) -> StarknetMessagingEvents<M> {
+    StarknetMessagingEvents::new(core_contract.address(), core_contract.client())
+}
+
+/// Whether an event of `event` matching `is_message` was emitted in the blocks of `options`,
+/// queried in ranges of [`MessageStatusOptions::block_range`] blocks up to the first match
+async fn find_event<M: Middleware, D: EthLogDecode>(
+    client: &Arc<M>,
+    mut event: Event<Arc<M>, M, D>,
+    options: &MessageStatusOptions,
+    is_message: impl Fn(&D) -> bool,
+) -> Result<bool, Error<M>> {
+    let latest_block = client
+        .get_block_number()
+        .await
+        .map_err(ContractError::from_middleware_error)?
+        .as_u64();
+
+    let mut from_block = options.from_block;
+    while from_block <= latest_block {
+        let to_block = latest_block.min(from_block.saturating_add(options.block_range - 1));
+        event.filter = event.filter.from_block(from_block).to_block(to_block);
+        if event.query().await?.iter().any(&is_message) {
+            return Ok(true);
+        }
+        from_block = to_block + 1;
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interfaces::StarknetMessagingTrait;
+    use crate::output::StarknetOsOutput;
+    use crate::{
+        deploy_and_initialize_starknet_core_contract, CoreContractClientType, CoreContractConfig,
+        CoreContractType,
+    };
+    use ethereum_instance::AnvilBuilder;
+    use starknet_proxy_client::deploy::{DeployOptions, ProxyVersion};
+
+    #[test]
+    fn test_message_hashes_match_os_output() {
+        let message_to_l1 = MessageToL1 {
+            from_address: 1.into(),
+            to_address: Address::repeat_byte(0x02),
+            payload: vec![3.into(), 4.into()],
+        };
+        let message_to_l2 = MessageToL2 {
+            from_address: Address::repeat_byte(0x05),
+            to_address: 6.into(),
+            nonce: 7.into(),
+            selector: 8.into(),
+            payload: vec![9.into()],
+        };
+        let output = StarknetOsOutput {
+            messages_to_l1: vec![message_to_l1.clone()],
+            messages_to_l2: vec![message_to_l2.clone()],
+            ..Default::default()
+        }
+        .encode();
+
+        // The core contract hashes the messages as laid out in the program output
+        let hash = |words: &[U256]| hash_words(words.iter());
+        assert_eq!(message_to_l1.hash(), hash(&output[11..16]));
+        assert_eq!(message_to_l2.hash(), hash(&output[17..23]));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires anvil"]
+    async fn test_l1_to_l2_message_status() {
+        let ethereum = AnvilBuilder::new().spawn().unwrap();
+        let client = ethereum.signer();
+        // Anvil mines a block per transaction, only wait for inclusion
+        let options = DeployOptions::default().confirmations(1);
+        let config = CoreContractConfig::new()
+            .program_hash(1)
+            .message_cancellation_delay(0);
+
+        let CoreContractClientType::Dev(core_contract) =
+            deploy_and_initialize_starknet_core_contract(
+                client.clone(),
+                ProxyVersion::UnsafeProxy,
+                CoreContractType::Dev,
+                &config,
+                &options,
+            )
+            .await
+            .unwrap()
+        else {
+            panic!("expected a dev core contract");
+        };
+
+        let message = MessageToL2 {
+            from_address: client.address(),
+            to_address: 1.into(),
+            nonce: 0.into(),
+            selector: 2.into(),
+            payload: vec![3.into()],
+        };
+        // Several ranges of blocks to look the events up in
+        let status_options = MessageStatusOptions::from_block(0).block_range(2);
+        let status = || core_contract.l1_to_l2_message_status(&message, &status_options);
+        assert_eq!(status().await.unwrap(), MessageStatus::NotSent);
+
+        let (to_address, selector, payload, nonce) = (
+            message.to_address,
+            message.selector,
+            message.payload.clone(),
+            message.nonce,
+        );
+        assert!(core_contract
+            .send_message_to_l2(to_address, selector, payload.clone(), 1.into())
+            .await
+            .unwrap()
+            .is_success());
+        assert_eq!(status().await.unwrap(), MessageStatus::Pending);
+
+        assert!(core_contract
+            .start_l1_to_l2_message_cancellation(to_address, selector, payload.clone(), nonce)
+            .await
+            .unwrap()
+            .is_success());
+        assert!(matches!(
+            status().await.unwrap(),
+            MessageStatus::CancellationRequested { .. }
+        ));
+
+        assert!(core_contract
+            .cancel_l1_to_l2_message(to_address, selector, payload, nonce)
+            .await
+            .unwrap()
+            .is_success());
+        assert_eq!(status().await.unwrap(), MessageStatus::Cancelled);
+
+        let message_to_l1 = MessageToL1 {
+            from_address: 1.into(),
+            to_address: client.address(),
+            payload: vec![],
+        };
+        assert_eq!(
+            core_contract
+                .l2_to_l1_message_status(&message_to_l1, &status_options)
+                .await
+                .unwrap(),
+            MessageStatus::NotSent
+        );
+    }
+}
@@ -0,0 +1,389 @@
+//! Starknet OS program output passed to `updateState` / `updateStateKzgDA`, laid out as
+//! described by `StarknetOutput` (`src/Output.sol`): a fixed size header, the KZG data if
+//! the state diff is published in blobs, then the L2 to L1 and L1 to L2 message segments.
+
+use ethers::types::{Address, U256};
+use thiserror::Error;
+
+const HEADER_SIZE: usize = 10;
+const MESSAGE_TO_L1_PREFIX_SIZE: usize = 3;
+const MESSAGE_TO_L2_PREFIX_SIZE: usize = 5;
+/// Message segment sizes and payload lengths are checked against this bound on-chain
+const MAX_SEGMENT_SIZE: u64 = 1 << 30;
+
+/// Previous block number of the output of the first block (-1 as a field element)
+pub const GENESIS_PREV_BLOCK_NUMBER: U256 = U256([0, 0, 0, 0x0800000000000011]);
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum OsOutputError {
+    #[error("Program output truncated: {0} words, more expected")]
+    Truncated(usize),
+    #[error("Program output too long: {0} unexpected words after the message segments")]
+    TooLong(usize),
+    #[error("Invalid `{0}` flag: {1}")]
+    InvalidFlag(&'static str, U256),
+    #[error("Invalid size {1} in the {0} segment")]
+    InvalidSize(&'static str, U256),
+    #[error("{commitments} KZG commitments for {evaluations} evaluations, one of each per blob expected")]
+    BlobCountMismatch {
+        commitments: usize,
+        evaluations: usize,
+    },
+    #[error("Messages of the {0} message segment overrun its size")]
+    MessageSegmentOverrun(&'static str),
+    #[error("Value {0:#x} is not an L1 address")]
+    InvalidAddress(U256),
+}
+
+/// Program output of the Starknet OS, see [`StarknetOsOutput::encode`] and
+/// [`StarknetOsOutput::decode`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StarknetOsOutput {
+    pub prev_state_root: U256,
+    pub new_state_root: U256,
+    /// [`GENESIS_PREV_BLOCK_NUMBER`] for the first block
+    pub prev_block_number: U256,
+    pub new_block_number: U256,
+    pub prev_block_hash: U256,
+    pub new_block_hash: U256,
+    /// Hash of the OS program for an aggregator run, zero otherwise
+    pub os_program_hash: U256,
+    pub config_hash: U256,
+    /// Not supported by the core contract
+    pub full_output: bool,
+    /// Set when the state diff is published in blobs (`updateStateKzgDA`)
+    pub kzg: Option<KzgOutput>,
+    pub messages_to_l1: Vec<MessageToL1>,
+    pub messages_to_l2: Vec<MessageToL2>,
+}
+
+/// KZG commitments of the blobs, commitments and evaluations being split in two words each
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KzgOutput {
+    z: U256,
+    commitments: Vec<[U256; 2]>,
+    evaluations: Vec<[U256; 2]>,
+}
+
+impl KzgOutput {
+    /// KZG output of the blobs evaluated at `z`, one commitment and one evaluation per blob
+    pub fn new(
+        z: U256,
+        commitments: Vec<[U256; 2]>,
+        evaluations: Vec<[U256; 2]>,
+    ) -> Result<Self, OsOutputError> {
+        if commitments.len() != evaluations.len() {
+            return Err(OsOutputError::BlobCountMismatch {
+                commitments: commitments.len(),
+                evaluations: evaluations.len(),
+            });
+        }
+        Ok(Self {
+            z,
+            commitments,
+            evaluations,
+        })
+    }
+
+    /// Point at which the blob polynomials are evaluated
+    pub fn z(&self) -> U256 {
+        self.z
+    }
+
+    /// One commitment per blob
+    pub fn commitments(&self) -> &[[U256; 2]] {
+        &self.commitments
+    }
+
+    /// Evaluation at `z` of each blob
+    pub fn evaluations(&self) -> &[[U256; 2]] {
+        &self.evaluations
+    }
+
+    pub fn n_blobs(&self) -> usize {
+        self.commitments.len()
+    }
+}
+
+/// Message sent from L2 to L1
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MessageToL1 {
+    pub from_address: U256,
+    pub to_address: Address,
+    pub payload: Vec<U256>,
+}
+
+/// Message sent from L1 to L2, consumed on L1 by the state update
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MessageToL2 {
+    pub from_address: Address,
+    pub to_address: U256,
+    pub nonce: U256,
+    pub selector: U256,
+    pub payload: Vec<U256>,
+}
+
+impl StarknetOsOutput {
+    /// Program output to pass to `update_state` / `update_state_kzg_da`
+    pub fn encode(&self) -> Vec<U256> {
+        let mut output = vec![
+            self.prev_state_root,
+            self.new_state_root,
+            self.prev_block_number,
+            self.new_block_number,
+            self.prev_block_hash,
+            self.new_block_hash,
+            self.os_program_hash,
+            self.config_hash,
+            U256::from(self.kzg.is_some() as u8),
+            U256::from(self.full_output as u8),
+        ];
+
+        if let Some(kzg) = &self.kzg {
+            output.push(kzg.z());
+            output.push(kzg.n_blobs().into());
+            output.extend(kzg.commitments().iter().flatten());
+            output.extend(kzg.evaluations().iter().flatten());
+        }
+
+        let messages_to_l1 = self.messages_to_l1.iter().flat_map(|message| {
+            [
+                message.from_address,
+                address_to_word(message.to_address),
+                message.payload.len().into(),
+            ]
+            .into_iter()
+            .chain(message.payload.iter().copied())
+        });
+        push_segment(&mut output, messages_to_l1);
+
+        let messages_to_l2 = self.messages_to_l2.iter().flat_map(|message| {
+            [
+                address_to_word(message.from_address),
+                message.to_address,
+                message.nonce,
+                message.selector,
+                message.payload.len().into(),
+            ]
+            .into_iter()
+            .chain(message.payload.iter().copied())
+        });
+        push_segment(&mut output, messages_to_l2);
+
+        output
+    }
+
+    /// Parses a program output, checking its layout the way the core contract does
+    pub fn decode(output: &[U256]) -> Result<Self, OsOutputError> {
+        let mut reader = Reader { output, offset: 0 };
+        let header = reader.take(HEADER_SIZE)?;
+        let use_kzg_da = flag("use_kzg_da", header[8])?;
+        let full_output = flag("full_output", header[9])?;
+
+        let kzg = if use_kzg_da {
+            let z = reader.take(1)?[0];
+            let n_blobs = reader.take(1)?[0];
+            let n_blobs = bounded(n_blobs).ok_or(OsOutputError::InvalidSize("kzg", n_blobs))?;
+            let commitments = words_pairs(reader.take(2 * n_blobs)?);
+            let evaluations = words_pairs(reader.take(2 * n_blobs)?);
+            Some(KzgOutput::new(z, commitments, evaluations)?)
+        } else {
+            None
+        };
+
+        let messages_to_l1 = reader
+            .segment("L2 to L1", MESSAGE_TO_L1_PREFIX_SIZE)?
+            .into_iter()
+            .map(|(prefix, payload)| {
+                Ok(MessageToL1 {
+                    from_address: prefix[0],
+                    to_address: word_to_address(prefix[1])?,
+                    payload,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        let messages_to_l2 = reader
+            .segment("L1 to L2", MESSAGE_TO_L2_PREFIX_SIZE)?
+            .into_iter()
+            .map(|(prefix, payload)| {
+                Ok(MessageToL2 {
+                    from_address: word_to_address(prefix[0])?,
+                    to_address: prefix[1],
+                    nonce: prefix[2],
+                    selector: prefix[3],
+                    payload,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        if reader.offset != output.len() {
+            return Err(OsOutputError::TooLong(output.len() - reader.offset));
+        }
+
+        Ok(Self {
+            prev_state_root: header[0],
+            new_state_root: header[1],
+            prev_block_number: header[2],
+            new_block_number: header[3],
+            prev_block_hash: header[4],
+            new_block_hash: header[5],
+            os_program_hash: header[6],
+            config_hash: header[7],
+            full_output,
+            kzg,
+            messages_to_l1,
+            messages_to_l2,
+        })
+    }
+}
+
+/// Messages of a segment, as their fixed size prefix and their payload
+type SegmentMessages<'a> = Vec<(&'a [U256], Vec<U256>)>;
+
+struct Reader<'a> {
+    output: &'a [U256],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [U256], OsOutputError> {
+        let words = self
+            .output
+            .get(self.offset..self.offset + len)
+            .ok_or(OsOutputError::Truncated(self.output.len()))?;
+        self.offset += len;
+        Ok(words)
+    }
+
+    /// Reads a message segment: its size followed by the messages, each one being a fixed
+    /// size prefix ending with the payload length, then the payload
+    fn segment(
+        &mut self,
+        name: &'static str,
+        prefix_size: usize,
+    ) -> Result<SegmentMessages<'a>, OsOutputError> {
+        let size = self.take(1)?[0];
+        let size = bounded(size).ok_or(OsOutputError::InvalidSize(name, size))?;
+        let mut segment = Reader {
+            output: self.take(size)?,
+            offset: 0,
+        };
+
+        let mut messages = Vec::new();
+        while segment.offset < segment.output.len() {
+            let overrun = |_| OsOutputError::MessageSegmentOverrun(name);
+            let prefix = segment.take(prefix_size).map_err(overrun)?;
+            let payload_length = prefix[prefix_size - 1];
+            let payload_length =
+                bounded(payload_length).ok_or(OsOutputError::InvalidSize(name, payload_length))?;
+            let payload = segment.take(payload_length).map_err(overrun)?;
+            messages.push((prefix, payload.to_vec()));
+        }
+        Ok(messages)
+    }
+}
+
+fn push_segment(output: &mut Vec<U256>, messages: impl Iterator<Item = U256>) {
+    let size_offset = output.len();
+    output.push(U256::zero());
+    output.extend(messages);
+    output[size_offset] = (output.len() - size_offset - 1).into();
+}
+
+fn bounded(size: U256) -> Option<usize> {
+    (size < U256::from(MAX_SEGMENT_SIZE)).then(|| size.as_usize())
+}
+
+fn flag(name: &'static str, value: U256) -> Result<bool, OsOutputError> {
+    if value > U256::one() {
+        return Err(OsOutputError::InvalidFlag(name, value));
+    }
+    Ok(value == U256::one())
+}
+
+fn words_pairs(words: &[U256]) -> Vec<[U256; 2]> {
+    words
+        .chunks_exact(2)
+        .map(|pair| [pair[0], pair[1]])
+        .collect()
+}
+
+pub(crate) fn address_to_word(address: Address) -> U256 {
+    U256::from_big_endian(address.as_bytes())
+}
+
+fn word_to_address(word: U256) -> Result<Address, OsOutputError> {
+    if word.bits() > 160 {
+        return Err(OsOutputError::InvalidAddress(word));
+    }
+    let mut bytes = [0u8; 32];
+    word.to_big_endian(&mut bytes);
+    Ok(Address::from_slice(&bytes[12..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_os_output_roundtrip() {
+        let os_output = StarknetOsOutput {
+            prev_state_root: 1.into(),
+            new_state_root: 2.into(),
+            prev_block_number: GENESIS_PREV_BLOCK_NUMBER,
+            new_block_number: 0.into(),
+            new_block_hash: 3.into(),
+            config_hash: 4.into(),
+            kzg: Some(
+                KzgOutput::new(
+                    5.into(),
+                    vec![[6.into(), 7.into()]],
+                    vec![[8.into(), 9.into()]],
+                )
+                .unwrap(),
+            ),
+            messages_to_l1: vec![MessageToL1 {
+                from_address: 10.into(),
+                to_address: Address::repeat_byte(0x11),
+                payload: vec![12.into(), 13.into()],
+            }],
+            messages_to_l2: vec![MessageToL2 {
+                from_address: Address::repeat_byte(0x14),
+                to_address: 15.into(),
+                nonce: 16.into(),
+                selector: 17.into(),
+                payload: vec![],
+            }],
+            ..Default::default()
+        };
+
+        let output = os_output.encode();
+        // header, z, blob count, commitment, evaluation, L2 to L1 and L1 to L2 segments
+        assert_eq!(output.len(), 10 + 2 + 2 + 2 + (1 + 5) + (1 + 5));
+        assert_eq!(output[16], 5.into());
+        assert_eq!(StarknetOsOutput::decode(&output), Ok(os_output));
+        assert_eq!(
+            StarknetOsOutput::decode(&output[..output.len() - 1]),
+            Err(OsOutputError::Truncated(output.len() - 1))
+        );
+
+        let mut invalid = output.clone();
+        invalid[11] = U256::MAX;
+        assert_eq!(
+            StarknetOsOutput::decode(&invalid),
+            Err(OsOutputError::InvalidSize("kzg", U256::MAX))
+        );
+    }
+
+    #[test]
+    fn test_kzg_output_blob_count() {
+        assert_eq!(
+            KzgOutput::new(1.into(), vec![[2.into(), 3.into()]], vec![]),
+            Err(OsOutputError::BlobCountMismatch {
+                commitments: 1,
+                evaluations: 0
+            })
+        );
+    }
+}
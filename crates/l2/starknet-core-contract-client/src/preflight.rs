@@ -0,0 +1,267 @@
+//! Checks of an `update_state` / `update_state_kzg_da` program output against the on-chain
+//! state of the core contract, reporting what the contract would revert on before sending it.
+
+use async_trait::async_trait;
+use ethers::middleware::Middleware;
+use ethers::types::{I256, U256};
+use starknet_proxy_client::interfaces::proxy::CoreContractState;
+use thiserror::Error;
+use utils::errors::Error;
+use utils::multicall::ViewBatch;
+use utils::StarknetContractClient;
+
+use crate::interfaces::StarknetCoreContractViewTrait;
+use crate::output::{StarknetOsOutput, GENESIS_PREV_BLOCK_NUMBER};
+
+/// Program output values must be field elements
+const FIELD_PRIME: U256 = U256([1, 0, 0, 0x0800000000000011]);
+
+/// Check of the core contract failing for a program output, with its revert reason
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum UpdateStateMismatch {
+    #[error("PROGRAM_OUTPUT_VALUE_OUT_OF_RANGE: word {index} is {value:#x}")]
+    ValueOutOfRange { index: usize, value: U256 },
+    #[error("INVALID_CONFIG_HASH: expected {expected:#x}, got {actual:#x}")]
+    ConfigHash { expected: U256, actual: U256 },
+    #[error("FULL_OUTPUT_NOT_SUPPORTED")]
+    FullOutput,
+    #[error("AGGREGATOR_MODE_INVALID_OS_PROGRAM_HASH: expected {expected:#x}, got {actual:#x}")]
+    OsProgramHash { expected: U256, actual: U256 },
+    #[error("UNEXPECTED_KZG_DA_FLAG: expected {expected}, got {actual}")]
+    KzgDaFlag { expected: bool, actual: bool },
+    #[error("INVALID_PREV_BLOCK_NUMBER: expected {expected:#x}, got {actual:#x}")]
+    PrevBlockNumber { expected: U256, actual: U256 },
+    #[error("INVALID_NEW_BLOCK_NUMBER: {actual:#x} is not after block {current}")]
+    NewBlockNumber { current: I256, actual: U256 },
+    #[error("INVALID_PREV_BLOCK_HASH: expected {expected:#x}, got {actual:#x}")]
+    PrevBlockHash { expected: U256, actual: U256 },
+    #[error("INVALID_PREVIOUS_ROOT: expected {expected:#x}, got {actual:#x}")]
+    PrevStateRoot { expected: U256, actual: U256 },
+}
+
+#[async_trait]
+pub trait UpdateStatePreflight<M: Middleware> {
+    /// Reads the state, program hash and config hash of the core contract and returns the
+    /// checks of `update_state` (`update_state_kzg_da` if `kzg_da`) failing for `os_output`,
+    /// none if it is expected to be accepted. The state transition proof is not checked.
+    /// The values are read with a single Multicall3 call (see [`ViewBatch`]) so that they all
+    /// come from the same block.
+    async fn preflight_update_state(
+        &self,
+        os_output: &StarknetOsOutput,
+        kzg_da: bool,
+    ) -> Result<Vec<UpdateStateMismatch>, Error<M>>;
+}
+
+#[async_trait]
+impl<T, M: Middleware> UpdateStatePreflight<M> for T
+where
+    T: StarknetCoreContractViewTrait<M> + StarknetContractClient<M> + Sync,
+{
+    async fn preflight_update_state(
+        &self,
+        os_output: &StarknetOsOutput,
+        kzg_da: bool,
+    ) -> Result<Vec<UpdateStateMismatch>, Error<M>> {
+        let mut batch = ViewBatch::new(self.client())?;
+        let state_root = batch.add(self.state_root_call());
+        let block_number = batch.add(self.state_block_number_call());
+        let block_hash = batch.add(self.state_block_hash_call());
+        let program_hash = batch.add(self.program_hash_call());
+        let config_hash = batch.add(self.config_hash_call());
+        let results = batch.call().await?;
+
+        let state = CoreContractState {
+            state_root: results.get(state_root)?,
+            block_number: results.get(block_number)?,
+            block_hash: results.get(block_hash)?,
+        };
+        let program_hash = results.get(program_hash)?;
+        let config_hash = results.get(config_hash)?;
+
+        Ok(check_update_state(
+            os_output,
+            &state,
+            program_hash,
+            config_hash,
+            kzg_da,
+        ))
+    }
+}
+
+/// Checks done by [`UpdateStatePreflight::preflight_update_state`] against the given
+/// on-chain values
+pub fn check_update_state(
+    os_output: &StarknetOsOutput,
+    state: &CoreContractState,
+    program_hash: U256,
+    config_hash: U256,
+    kzg_da: bool,
+) -> Vec<UpdateStateMismatch> {
+    let mut mismatches = Vec::new();
+
+    if let Some((index, value)) = os_output
+        .encode()
+        .into_iter()
+        .enumerate()
+        .find(|(_, value)| *value >= FIELD_PRIME)
+    {
+        mismatches.push(UpdateStateMismatch::ValueOutOfRange { index, value });
+    }
+    if os_output.config_hash != config_hash {
+        mismatches.push(UpdateStateMismatch::ConfigHash {
+            expected: config_hash,
+            actual: os_output.config_hash,
+        });
+    }
+    if os_output.full_output {
+        mismatches.push(UpdateStateMismatch::FullOutput);
+    }
+    // Outputs of an aggregator run carry the OS program hash, zero otherwise
+    if !os_output.os_program_hash.is_zero() && os_output.os_program_hash != program_hash {
+        mismatches.push(UpdateStateMismatch::OsProgramHash {
+            expected: program_hash,
+            actual: os_output.os_program_hash,
+        });
+    }
+    if os_output.kzg.is_some() != kzg_da {
+        mismatches.push(UpdateStateMismatch::KzgDaFlag {
+            expected: kzg_da,
+            actual: os_output.kzg.is_some(),
+        });
+    }
+
+    let expected_prev_block_number = if state.block_number == I256::minus_one() {
+        GENESIS_PREV_BLOCK_NUMBER
+    } else {
+        state.block_number.into_raw()
+    };
+    if os_output.prev_block_number != expected_prev_block_number {
+        mismatches.push(UpdateStateMismatch::PrevBlockNumber {
+            expected: expected_prev_block_number,
+            actual: os_output.prev_block_number,
+        });
+    }
+    if I256::from_raw(os_output.new_block_number) <= state.block_number {
+        mismatches.push(UpdateStateMismatch::NewBlockNumber {
+            current: state.block_number,
+            actual: os_output.new_block_number,
+        });
+    }
+    if os_output.prev_block_hash != state.block_hash {
+        mismatches.push(UpdateStateMismatch::PrevBlockHash {
+            expected: state.block_hash,
+            actual: os_output.prev_block_hash,
+        });
+    }
+    if os_output.prev_state_root != state.state_root {
+        mismatches.push(UpdateStateMismatch::PrevStateRoot {
+            expected: state.state_root,
+            actual: os_output.prev_state_root,
+        });
+    }
+
+    mismatches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        deploy_and_initialize_starknet_core_contract, CoreContractClientType, CoreContractConfig,
+        CoreContractType,
+    };
+    use ethereum_instance::AnvilBuilder;
+    use starknet_proxy_client::deploy::{DeployOptions, ProxyVersion};
+
+    #[test]
+    fn test_check_update_state() {
+        let state = CoreContractState {
+            state_root: 1.into(),
+            block_number: I256::minus_one(),
+            block_hash: U256::zero(),
+        };
+        let mut os_output = StarknetOsOutput {
+            prev_state_root: 1.into(),
+            new_state_root: 2.into(),
+            prev_block_number: GENESIS_PREV_BLOCK_NUMBER,
+            config_hash: 3.into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            check_update_state(&os_output, &state, 4.into(), 3.into(), false),
+            vec![]
+        );
+
+        os_output.prev_block_number = U256::zero();
+        os_output.os_program_hash = 5.into();
+        assert_eq!(
+            check_update_state(&os_output, &state, 4.into(), 3.into(), true),
+            vec![
+                UpdateStateMismatch::OsProgramHash {
+                    expected: 4.into(),
+                    actual: 5.into(),
+                },
+                UpdateStateMismatch::KzgDaFlag {
+                    expected: true,
+                    actual: false,
+                },
+                UpdateStateMismatch::PrevBlockNumber {
+                    expected: GENESIS_PREV_BLOCK_NUMBER,
+                    actual: U256::zero(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "requires anvil"]
+    async fn test_preflight_update_state() {
+        let ethereum = AnvilBuilder::new().spawn().unwrap();
+        // Anvil mines a block per transaction, only wait for inclusion
+        let options = DeployOptions::default().confirmations(1);
+        ethereum
+            .ensure_multicall3(options.artifact_source())
+            .await
+            .unwrap();
+        let config = CoreContractConfig::new().program_hash(1).config_hash(2);
+
+        let CoreContractClientType::Dev(core_contract) =
+            deploy_and_initialize_starknet_core_contract(
+                ethereum.signer(),
+                ProxyVersion::UnsafeProxy,
+                CoreContractType::Dev,
+                &config,
+                &options,
+            )
+            .await
+            .unwrap()
+        else {
+            panic!("expected a dev core contract");
+        };
+
+        let mut os_output = StarknetOsOutput {
+            prev_block_number: GENESIS_PREV_BLOCK_NUMBER,
+            config_hash: 2.into(),
+            ..Default::default()
+        };
+        let mismatches = core_contract
+            .preflight_update_state(&os_output, false)
+            .await
+            .unwrap();
+        assert_eq!(mismatches, vec![]);
+
+        os_output.config_hash = 3.into();
+        let mismatches = core_contract
+            .preflight_update_state(&os_output, false)
+            .await
+            .unwrap();
+        assert_eq!(
+            mismatches,
+            vec![UpdateStateMismatch::ConfigHash {
+                expected: 2.into(),
+                actual: 3.into(),
+            }]
+        );
+    }
+}
@@ -1,30 +1,44 @@
 use std::sync::Arc;
 
 use crate::interfaces::erc20::ERC20Token;
-use utils::{LocalWalletSignerMiddleware, StarknetContractClient};
+use utils::registry::DeploymentEntry;
+use utils::{LocalWalletSignerMiddleware, ReadOnly, ReadWrite, StarknetContractClient};
 
+use ethers::middleware::Middleware;
 use ethers::types::Address;
 
 #[derive(Clone)]
-pub struct ERC20ContractClient {
-    erc20_token: ERC20Token<LocalWalletSignerMiddleware>,
+pub struct ERC20ContractClient<M = LocalWalletSignerMiddleware> {
+    erc20_token: ERC20Token<M>,
 }
 
-impl ERC20ContractClient {
-    pub fn new(address: Address, client: Arc<LocalWalletSignerMiddleware>) -> Self {
+impl<M: Middleware> ERC20ContractClient<M> {
+    pub fn new(address: Address, client: Arc<M>) -> Self {
         Self {
             erc20_token: ERC20Token::new(address, client.clone()),
         }
     }
+
+    /// Client of a deployment recorded in a [`utils::registry::DeploymentRegistry`]
+    pub fn from_registry_entry(entry: &DeploymentEntry, client: Arc<M>) -> Self {
+        Self::new(entry.address, client)
+    }
+
+    /// Read-only client, typically over a plain `Provider`: only view methods can be called.
+    pub fn read_only(address: Address, client: Arc<M>) -> ReadOnly<Self> {
+        ReadOnly::new(Self::new(address, client))
+    }
 }
 
-impl AsRef<ERC20Token<LocalWalletSignerMiddleware>> for ERC20ContractClient {
-    fn as_ref(&self) -> &ERC20Token<LocalWalletSignerMiddleware> {
+impl<M> AsRef<ERC20Token<M>> for ERC20ContractClient<M> {
+    fn as_ref(&self) -> &ERC20Token<M> {
         &self.erc20_token
     }
 }
 
-impl StarknetContractClient for ERC20ContractClient {
+impl<M> ReadWrite for ERC20ContractClient<M> {}
+
+impl<M: Middleware> StarknetContractClient<M> for ERC20ContractClient<M> {
     fn address(&self) -> Address {
         self.erc20_token.address()
     }
@@ -33,7 +47,7 @@ impl StarknetContractClient for ERC20ContractClient {
         self.erc20_token.address()
     }
 
-    fn client(&self) -> Arc<LocalWalletSignerMiddleware> {
+    fn client(&self) -> Arc<M> {
         self.erc20_token.client()
     }
 }
@@ -1,6 +1,6 @@
 use async_trait::async_trait;
-use ethers::contract::ContractError;
-use ethers::prelude::TransactionReceipt;
+use ethers::contract::ContractCall;
+use ethers::types::transaction::eip2718::TypedTransaction;
 use ethers::{
     prelude::abigen,
     providers::Middleware,
@@ -8,77 +8,122 @@ use ethers::{
 };
 
 use utils::errors::Error;
+use utils::transaction::{
+    send_call, simulate_call, SimulationOptions, SimulationOutcome, TransactionOutcome,
+};
+use utils::ContractReader;
 
-abigen!(
-    ERC20Token,
-    "../../../artifacts/starkgate-contracts/ERC20.json",
-);
+abigen!(ERC20Token, "abi/ERC20.json");
 
 #[async_trait]
 pub trait ERC20TokenTrait<M: Middleware> {
+    async fn approve(&self, address: Address, value: U256) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_approve(
+        &self,
+        address: Address,
+        value: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<bool>, Error<M>>;
+    fn build_approve(&self, address: Address, value: U256) -> TypedTransaction;
+}
+
+#[async_trait]
+impl<T, M: Middleware> ERC20TokenTrait<M> for T
+where
+    T: AsRef<ERC20Token<M>> + Send + Sync,
+{
+    async fn approve(&self, address: Address, value: U256) -> Result<TransactionOutcome, Error<M>> {
+        send_call(self.as_ref(), self.as_ref().approve(address, value)).await
+    }
+
+    async fn simulate_approve(
+        &self,
+        address: Address,
+        value: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<bool>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().approve(address, value),
+            options,
+        )
+        .await
+    }
+
+    fn build_approve(&self, address: Address, value: U256) -> TypedTransaction {
+        self.as_ref().approve(address, value).tx
+    }
+}
+
+/// View methods of [`ERC20TokenTrait`]
+#[async_trait]
+pub trait ERC20TokenViewTrait<M: Middleware> {
     async fn name(&self) -> Result<String, Error<M>>;
+    fn name_call(&self) -> ContractCall<M, String>;
     async fn symbol(&self) -> Result<String, Error<M>>;
+    fn symbol_call(&self) -> ContractCall<M, String>;
     async fn total_supply(&self) -> Result<U256, Error<M>>;
+    fn total_supply_call(&self) -> ContractCall<M, U256>;
     async fn balance_of(&self, address: Address) -> Result<U256, Error<M>>;
+    fn balance_of_call(&self, address: Address) -> ContractCall<M, U256>;
     async fn allowance(&self, owner: Address, spender: Address) -> Result<U256, Error<M>>;
-    async fn approve(&self, address: Address, value: U256) -> Result<bool, Error<M>>;
+    fn allowance_call(&self, owner: Address, spender: Address) -> ContractCall<M, U256>;
 }
 
 #[async_trait]
-impl<T, M: Middleware> ERC20TokenTrait<M> for T
+impl<T, M: Middleware> ERC20TokenViewTrait<M> for T
 where
-    T: AsRef<ERC20Token<M>> + Send + Sync,
+    T: ContractReader<ERC20Token<M>> + Send + Sync,
 {
     async fn name(&self) -> Result<String, Error<M>> {
-        self.as_ref().name().call().await.map_err(Into::into)
+        self.reader().name().call().await.map_err(Into::into)
+    }
+
+    fn name_call(&self) -> ContractCall<M, String> {
+        self.reader().name()
     }
 
     async fn symbol(&self) -> Result<String, Error<M>> {
-        self.as_ref().symbol().call().await.map_err(Into::into)
+        self.reader().symbol().call().await.map_err(Into::into)
+    }
+
+    fn symbol_call(&self) -> ContractCall<M, String> {
+        self.reader().symbol()
     }
 
     async fn total_supply(&self) -> Result<U256, Error<M>> {
-        self.as_ref()
+        self.reader()
             .total_supply()
             .call()
             .await
             .map_err(Into::into)
     }
 
+    fn total_supply_call(&self) -> ContractCall<M, U256> {
+        self.reader().total_supply()
+    }
+
     async fn balance_of(&self, address: Address) -> Result<U256, Error<M>> {
-        self.as_ref()
+        self.reader()
             .balance_of(address)
             .call()
             .await
             .map_err(Into::into)
     }
 
+    fn balance_of_call(&self, address: Address) -> ContractCall<M, U256> {
+        self.reader().balance_of(address)
+    }
+
     async fn allowance(&self, owner: Address, spender: Address) -> Result<U256, Error<M>> {
-        self.as_ref()
+        self.reader()
             .allowance(owner, spender)
             .call()
             .await
             .map_err(Into::into)
     }
 
-    async fn approve(&self, address: Address, value: U256) -> Result<bool, Error<M>> {
-        let txn: Result<Option<TransactionReceipt>, Error<M>> = self
-            .as_ref()
-            .approve(address, value)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into);
-
-        match txn {
-            Ok(receipt) => {
-                if receipt.is_some() {
-                    return Ok(true);
-                }
-                Ok(false)
-            }
-            Err(err) => Err(err),
-        }
+    fn allowance_call(&self, owner: Address, spender: Address) -> ContractCall<M, U256> {
+        self.reader().allowance(owner, spender)
     }
 }
@@ -1,53 +1,76 @@
 use std::sync::Arc;
 
 use crate::interfaces::eth_bridge::StarknetEthBridge;
-use utils::{LocalWalletSignerMiddleware, StarknetContractClient};
+use starknet_proxy_client::implementation::resolve_implementation;
+use utils::errors::Error;
+use utils::registry::DeploymentEntry;
+use utils::{LocalWalletSignerMiddleware, ReadOnly, ReadWrite, StarknetContractClient};
 
+use ethers::middleware::Middleware;
 use ethers::types::Address;
 use starknet_proxy_client::clients::proxy_3_0_2::ProxySupport3_0_2;
 
 /// Client to interact with a Starknet Eth Bridge
 #[derive(Clone)]
-pub struct StarknetEthBridgeContractClient {
-    eth_bridge: StarknetEthBridge<LocalWalletSignerMiddleware>,
-    proxy_support: ProxySupport3_0_2<LocalWalletSignerMiddleware>,
+pub struct StarknetEthBridgeContractClient<M = LocalWalletSignerMiddleware> {
+    eth_bridge: StarknetEthBridge<M>,
+    proxy_support: ProxySupport3_0_2<M>,
     eth_bridge_implementation: Address,
 }
 
-impl StarknetEthBridgeContractClient {
-    pub fn new(
-        address: Address,
-        client: Arc<LocalWalletSignerMiddleware>,
-        implementation_address: Address,
-    ) -> Self {
+impl<M: Middleware> StarknetEthBridgeContractClient<M> {
+    pub fn new(address: Address, client: Arc<M>, implementation_address: Address) -> Self {
         Self {
             eth_bridge: StarknetEthBridge::new(address, client.clone()),
             proxy_support: ProxySupport3_0_2::new(address, client.clone()),
             eth_bridge_implementation: implementation_address,
         }
     }
+
+    /// Client of a deployment behind a proxy, its implementation being read on-chain
+    /// (see [`resolve_implementation`])
+    pub async fn attach(address: Address, client: Arc<M>) -> Result<Self, Error<M>> {
+        let implementation_address = resolve_implementation(address, client.clone()).await?;
+        Ok(Self::new(address, client, implementation_address))
+    }
+
+    /// Client of a deployment recorded in a [`utils::registry::DeploymentRegistry`]
+    pub fn from_registry_entry(entry: &DeploymentEntry, client: Arc<M>) -> Self {
+        Self::new(entry.address, client, entry.implementation)
+    }
+
+    /// Read-only client, typically over a plain `Provider`: only view methods can be called.
+    pub fn read_only(
+        address: Address,
+        client: Arc<M>,
+        implementation_address: Address,
+    ) -> ReadOnly<Self> {
+        ReadOnly::new(Self::new(address, client, implementation_address))
+    }
 }
 
-impl AsRef<StarknetEthBridge<LocalWalletSignerMiddleware>> for StarknetEthBridgeContractClient {
-    fn as_ref(&self) -> &StarknetEthBridge<LocalWalletSignerMiddleware> {
+impl<M> AsRef<StarknetEthBridge<M>> for StarknetEthBridgeContractClient<M> {
+    fn as_ref(&self) -> &StarknetEthBridge<M> {
         &self.eth_bridge
     }
 }
 
-impl AsRef<ProxySupport3_0_2<LocalWalletSignerMiddleware>> for StarknetEthBridgeContractClient {
-    fn as_ref(&self) -> &ProxySupport3_0_2<LocalWalletSignerMiddleware> {
+impl<M> AsRef<ProxySupport3_0_2<M>> for StarknetEthBridgeContractClient<M> {
+    fn as_ref(&self) -> &ProxySupport3_0_2<M> {
         &self.proxy_support
     }
 }
 
-impl StarknetContractClient for StarknetEthBridgeContractClient {
+impl<M> ReadWrite for StarknetEthBridgeContractClient<M> {}
+
+impl<M: Middleware> StarknetContractClient<M> for StarknetEthBridgeContractClient<M> {
     fn address(&self) -> Address {
         self.eth_bridge.address()
     }
     fn implementation_address(&self) -> Address {
         self.eth_bridge_implementation
     }
-    fn client(&self) -> Arc<LocalWalletSignerMiddleware> {
+    fn client(&self) -> Arc<M> {
         self.eth_bridge.client()
     }
 }
@@ -1,47 +1,74 @@
 use async_trait::async_trait;
+use ethers::contract::ContractCall;
 use ethers::prelude::H160;
-use ethers::{
-    contract::ContractError,
-    prelude::abigen,
-    providers::Middleware,
-    types::{TransactionReceipt, U256},
-};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::{prelude::abigen, providers::Middleware, types::U256};
 
 use utils::errors::Error;
+use utils::transaction::{
+    send_call, simulate_call, SimulationOptions, SimulationOutcome, TransactionOutcome,
+};
+use utils::ContractReader;
 
 type Address = H160;
 
-abigen!(
-    StarknetEthBridge,
-    "../../../artifacts/starkgate-contracts-0.9/StarknetLegacyBridge.json",
-);
+abigen!(StarknetEthBridge, "abi/StarknetLegacyBridge.json");
 
 #[async_trait]
 pub trait StarknetEthBridgeTrait<M: Middleware> {
     async fn set_max_total_balance(
         &self,
         max_total_balance: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
-    async fn set_max_deposit(
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_set_max_total_balance(
+        &self,
+        max_total_balance: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_set_max_total_balance(&self, max_total_balance: U256) -> TypedTransaction;
+    async fn set_max_deposit(&self, max_deposit: U256) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_set_max_deposit(
         &self,
         max_deposit: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_set_max_deposit(&self, max_deposit: U256) -> TypedTransaction;
     async fn set_l2_token_bridge(
         &self,
         l2_token_bridge: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_set_l2_token_bridge(
+        &self,
+        l2_token_bridge: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_set_l2_token_bridge(&self, l2_token_bridge: U256) -> TypedTransaction;
     async fn deposit(
         &self,
         amount: U256,
         l2_recipient: U256,
         fee: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_deposit(
+        &self,
+        amount: U256,
+        l2_recipient: U256,
+        fee: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_deposit(&self, amount: U256, l2_recipient: U256, fee: U256) -> TypedTransaction;
     async fn withdraw(
         &self,
         amount: U256,
         l1_recipient: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
-    async fn identify(&self) -> Result<String, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_withdraw(
+        &self,
+        amount: U256,
+        l1_recipient: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_withdraw(&self, amount: U256, l1_recipient: Address) -> TypedTransaction;
 }
 
 #[async_trait]
@@ -52,40 +79,78 @@ where
     async fn set_max_total_balance(
         &self,
         max_total_balance: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .set_max_total_balance(max_total_balance)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref().set_max_total_balance(max_total_balance),
+        )
+        .await
     }
 
-    async fn set_max_deposit(
+    async fn simulate_set_max_total_balance(
+        &self,
+        max_total_balance: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().set_max_total_balance(max_total_balance),
+            options,
+        )
+        .await
+    }
+
+    fn build_set_max_total_balance(&self, max_total_balance: U256) -> TypedTransaction {
+        self.as_ref().set_max_total_balance(max_total_balance).tx
+    }
+
+    async fn set_max_deposit(&self, max_deposit: U256) -> Result<TransactionOutcome, Error<M>> {
+        send_call(self.as_ref(), self.as_ref().set_max_deposit(max_deposit)).await
+    }
+
+    async fn simulate_set_max_deposit(
         &self,
         max_deposit: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .set_max_deposit(max_deposit)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().set_max_deposit(max_deposit),
+            options,
+        )
+        .await
+    }
+
+    fn build_set_max_deposit(&self, max_deposit: U256) -> TypedTransaction {
+        self.as_ref().set_max_deposit(max_deposit).tx
     }
 
     async fn set_l2_token_bridge(
         &self,
         l2_token_bridge: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .set_l2_token_bridge(l2_token_bridge)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref().set_l2_token_bridge(l2_token_bridge),
+        )
+        .await
+    }
+
+    async fn simulate_set_l2_token_bridge(
+        &self,
+        l2_token_bridge: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().set_l2_token_bridge(l2_token_bridge),
+            options,
+        )
+        .await
+    }
+
+    fn build_set_l2_token_bridge(&self, l2_token_bridge: U256) -> TypedTransaction {
+        self.as_ref().set_l2_token_bridge(l2_token_bridge).tx
     }
 
     async fn deposit(
@@ -93,32 +158,90 @@ where
         amount: U256,
         l2_recipient: U256,
         fee: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref()
+                .deposit_with_amount(amount, l2_recipient)
+                .value(fee),
+        )
+        .await
+    }
+
+    async fn simulate_deposit(
+        &self,
+        amount: U256,
+        l2_recipient: U256,
+        fee: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref()
+                .deposit_with_amount(amount, l2_recipient)
+                .value(fee),
+            options,
+        )
+        .await
+    }
+
+    fn build_deposit(&self, amount: U256, l2_recipient: U256, fee: U256) -> TypedTransaction {
         self.as_ref()
             .deposit_with_amount(amount, l2_recipient)
             .value(fee)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+            .tx
     }
 
     async fn withdraw(
         &self,
         amount: U256,
         l1_recipient: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref().withdraw_with_recipient(amount, l1_recipient),
+        )
+        .await
+    }
+
+    async fn simulate_withdraw(
+        &self,
+        amount: U256,
+        l1_recipient: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().withdraw_with_recipient(amount, l1_recipient),
+            options,
+        )
+        .await
+    }
+
+    fn build_withdraw(&self, amount: U256, l1_recipient: Address) -> TypedTransaction {
         self.as_ref()
             .withdraw_with_recipient(amount, l1_recipient)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+            .tx
     }
+}
+
+/// View methods of [`StarknetEthBridgeTrait`]
+#[async_trait]
+pub trait StarknetEthBridgeViewTrait<M: Middleware> {
+    async fn identify(&self) -> Result<String, Error<M>>;
+    fn identify_call(&self) -> ContractCall<M, String>;
+}
 
+#[async_trait]
+impl<T, M: Middleware> StarknetEthBridgeViewTrait<M> for T
+where
+    T: ContractReader<StarknetEthBridge<M>> + Send + Sync,
+{
     async fn identify(&self) -> Result<String, Error<M>> {
-        self.as_ref().identify().call().await.map_err(Into::into)
+        self.reader().identify().call().await.map_err(Into::into)
+    }
+
+    fn identify_call(&self) -> ContractCall<M, String> {
+        self.reader().identify()
     }
 }
@@ -1,25 +1,33 @@
 use std::sync::Arc;
 
 use clients::eth_bridge::StarknetEthBridgeContractClient;
-use starknet_proxy_client::deploy::{deploy_contract_behind_proxy, Error, ProxyVersion};
+use starknet_proxy_client::deploy::{
+    bundled_artifact, deploy_contract_behind_proxy, DeployOptions, Error, ProxyVersion,
+};
+use utils::errors::{ErrorContext, ResultExt};
 use utils::{LocalWalletSignerMiddleware, NO_CONSTRUCTOR_ARG};
 pub mod clients;
 pub mod interfaces;
 
-const STARKNET_ETH_BRIDGE: &str =
-    include_str!("../../../../artifacts/starkgate-contracts-0.9/StarknetLegacyBridge.json");
+bundled_artifact!(
+    STARKNET_ETH_BRIDGE,
+    "starkgate-contracts-0.9/StarknetLegacyBridge.json"
+);
 
 pub async fn deploy_starknet_eth_bridge_behind_unsafe_proxy(
     client: Arc<LocalWalletSignerMiddleware>,
+    options: &DeployOptions,
 ) -> Result<StarknetEthBridgeContractClient, Error> {
     // Deploy the Eth Bridge contract (no explicit constructor)
     let eth_bridge_contract = deploy_contract_behind_proxy(
         client.clone(),
-        STARKNET_ETH_BRIDGE,
+        &options.artifact_source().load(&STARKNET_ETH_BRIDGE)?,
         NO_CONSTRUCTOR_ARG,
         ProxyVersion::UnsafeProxy,
+        options,
     )
-    .await?;
+    .await
+    .context(ErrorContext::new().contract("StarknetLegacyBridge"))?;
 
     Ok(StarknetEthBridgeContractClient::new(
         eth_bridge_contract.0.address(),
@@ -30,15 +38,18 @@ pub async fn deploy_starknet_eth_bridge_behind_unsafe_proxy(
 
 pub async fn deploy_starknet_eth_bridge_behind_safe_proxy(
     client: Arc<LocalWalletSignerMiddleware>,
+    options: &DeployOptions,
 ) -> Result<StarknetEthBridgeContractClient, Error> {
     // Deploy the Eth Bridge contract (no explicit constructor)
     let (eth_bridge_contract, eth_bridge_contract_implementation) = deploy_contract_behind_proxy(
         client.clone(),
-        STARKNET_ETH_BRIDGE,
+        &options.artifact_source().load(&STARKNET_ETH_BRIDGE)?,
         NO_CONSTRUCTOR_ARG,
         ProxyVersion::SafeProxy3_0_2,
+        options,
     )
-    .await?;
+    .await
+    .context(ErrorContext::new().contract("StarknetLegacyBridge"))?;
 
     Ok(StarknetEthBridgeContractClient::new(
         eth_bridge_contract.address(),
@@ -1,51 +1,99 @@
 use crate::interfaces::proxy::{ProxyInitializeData, ProxySupport5_0_0Trait};
 use async_trait::async_trait;
 use ethers::addressbook::Address;
-use ethers::contract::{abigen, ContractError};
+use ethers::contract::abigen;
 use ethers::middleware::Middleware;
-use ethers::prelude::{Bytes, TransactionReceipt};
+use ethers::prelude::Bytes;
+use ethers::types::transaction::eip2718::TypedTransaction;
 use utils::errors::Error;
+use utils::transaction::{
+    send_call, simulate_call, SimulationOptions, SimulationOutcome, TransactionOutcome,
+};
 
-abigen!(
-    ProxySupport5_0_0,
-    "../../../artifacts/starkgate-contracts/Proxy_5_0_0.json",
-);
+abigen!(ProxySupport5_0_0, "abi/Proxy_5_0_0.json");
 
 #[async_trait]
 impl<T, M: Middleware> ProxySupport5_0_0Trait<M> for T
 where
     T: AsRef<ProxySupport5_0_0<M>> + Send + Sync,
 {
-    async fn initialize(&self, data: Bytes) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .initialize(data)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    async fn initialize(&self, data: Bytes) -> Result<TransactionOutcome, Error<M>> {
+        send_call(self.as_ref(), self.as_ref().initialize(data)).await
+    }
+
+    async fn simulate_initialize(
+        &self,
+        data: Bytes,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(self.as_ref(), self.as_ref().initialize(data), options).await
+    }
+
+    fn build_initialize(&self, data: Bytes) -> TypedTransaction {
+        self.as_ref().initialize(data).tx
     }
 
     async fn initialize_with<const N: usize>(
         &self,
         data: ProxyInitializeData<N>,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
+    ) -> Result<TransactionOutcome, Error<M>> {
         self.initialize(data.into()).await
     }
 
+    async fn simulate_initialize_with<const N: usize>(
+        &self,
+        data: ProxyInitializeData<N>,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        self.simulate_initialize(data.into(), options).await
+    }
+
+    fn build_initialize_with<const N: usize>(
+        &self,
+        data: ProxyInitializeData<N>,
+    ) -> TypedTransaction {
+        self.build_initialize(data.into())
+    }
+
     async fn upgrade_to(
         &self,
         data: Bytes,
         implementation_address: Address,
         finalized: bool,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref()
+                .upgrade_to(implementation_address, data, finalized),
+        )
+        .await
+    }
+
+    async fn simulate_upgrade_to(
+        &self,
+        data: Bytes,
+        implementation_address: Address,
+        finalized: bool,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref()
+                .upgrade_to(implementation_address, data, finalized),
+            options,
+        )
+        .await
+    }
+
+    fn build_upgrade_to(
+        &self,
+        data: Bytes,
+        implementation_address: Address,
+        finalized: bool,
+    ) -> TypedTransaction {
         self.as_ref()
             .upgrade_to(implementation_address, data, finalized)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+            .tx
     }
 
     async fn add_implementation(
@@ -53,143 +101,297 @@ where
         data: Bytes,
         implementation_address: Address,
         finalized: bool,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref()
+                .add_implementation(implementation_address, data, finalized),
+        )
+        .await
+    }
+
+    async fn simulate_add_implementation(
+        &self,
+        data: Bytes,
+        implementation_address: Address,
+        finalized: bool,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref()
+                .add_implementation(implementation_address, data, finalized),
+            options,
+        )
+        .await
+    }
+
+    fn build_add_implementation(
+        &self,
+        data: Bytes,
+        implementation_address: Address,
+        finalized: bool,
+    ) -> TypedTransaction {
         self.as_ref()
             .add_implementation(implementation_address, data, finalized)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+            .tx
     }
 
     async fn proxy_nominate_new_governor(
         &self,
         new_governor: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .register_app_governor(new_governor)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref().register_app_governor(new_governor),
+        )
+        .await
+    }
+
+    async fn simulate_proxy_nominate_new_governor(
+        &self,
+        new_governor: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().register_app_governor(new_governor),
+            options,
+        )
+        .await
+    }
+
+    fn build_proxy_nominate_new_governor(&self, new_governor: Address) -> TypedTransaction {
+        self.as_ref().register_app_governor(new_governor).tx
     }
 
     async fn proxy_remove_governance(
         &self,
         governor: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .revoke_app_governor(governor)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(self.as_ref(), self.as_ref().revoke_app_governor(governor)).await
+    }
+
+    async fn simulate_proxy_remove_governance(
+        &self,
+        governor: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().revoke_app_governor(governor),
+            options,
+        )
+        .await
+    }
+
+    fn build_proxy_remove_governance(&self, governor: Address) -> TypedTransaction {
+        self.as_ref().revoke_app_governor(governor).tx
     }
 
     async fn register_app_governor(
         &self,
         account: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .register_app_governor(account)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(self.as_ref(), self.as_ref().register_app_governor(account)).await
+    }
+
+    async fn simulate_register_app_governor(
+        &self,
+        account: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().register_app_governor(account),
+            options,
+        )
+        .await
+    }
+
+    fn build_register_app_governor(&self, account: Address) -> TypedTransaction {
+        self.as_ref().register_app_governor(account).tx
     }
 
     async fn register_app_role_admin(
         &self,
         account: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .register_app_role_admin(account)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref().register_app_role_admin(account),
+        )
+        .await
+    }
+
+    async fn simulate_register_app_role_admin(
+        &self,
+        account: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().register_app_role_admin(account),
+            options,
+        )
+        .await
+    }
+
+    fn build_register_app_role_admin(&self, account: Address) -> TypedTransaction {
+        self.as_ref().register_app_role_admin(account).tx
     }
 
     async fn register_governance_admin(
         &self,
         account: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .register_governance_admin(account)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref().register_governance_admin(account),
+        )
+        .await
     }
 
-    async fn register_operator(
+    async fn simulate_register_governance_admin(
         &self,
         account: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .register_operator(account)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().register_governance_admin(account),
+            options,
+        )
+        .await
+    }
+
+    fn build_register_governance_admin(&self, account: Address) -> TypedTransaction {
+        self.as_ref().register_governance_admin(account).tx
+    }
+
+    async fn register_operator(&self, account: Address) -> Result<TransactionOutcome, Error<M>> {
+        send_call(self.as_ref(), self.as_ref().register_operator(account)).await
+    }
+
+    async fn simulate_register_operator(
+        &self,
+        account: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().register_operator(account),
+            options,
+        )
+        .await
+    }
+
+    fn build_register_operator(&self, account: Address) -> TypedTransaction {
+        self.as_ref().register_operator(account).tx
     }
 
     async fn register_security_admin(
         &self,
         account: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .register_security_admin(account)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref().register_security_admin(account),
+        )
+        .await
+    }
+
+    async fn simulate_register_security_admin(
+        &self,
+        account: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().register_security_admin(account),
+            options,
+        )
+        .await
+    }
+
+    fn build_register_security_admin(&self, account: Address) -> TypedTransaction {
+        self.as_ref().register_security_admin(account).tx
     }
 
     async fn register_security_agent(
         &self,
         account: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .register_security_agent(account)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref().register_security_agent(account),
+        )
+        .await
     }
 
-    async fn register_token_admin(
+    async fn simulate_register_security_agent(
         &self,
         account: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .register_token_admin(account)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().register_security_agent(account),
+            options,
+        )
+        .await
+    }
+
+    fn build_register_security_agent(&self, account: Address) -> TypedTransaction {
+        self.as_ref().register_security_agent(account).tx
+    }
+
+    async fn register_token_admin(&self, account: Address) -> Result<TransactionOutcome, Error<M>> {
+        send_call(self.as_ref(), self.as_ref().register_token_admin(account)).await
+    }
+
+    async fn simulate_register_token_admin(
+        &self,
+        account: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().register_token_admin(account),
+            options,
+        )
+        .await
+    }
+
+    fn build_register_token_admin(&self, account: Address) -> TypedTransaction {
+        self.as_ref().register_token_admin(account).tx
     }
 
     async fn register_upgrade_governor(
         &self,
         account: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .register_upgrade_governor(account)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref().register_upgrade_governor(account),
+        )
+        .await
+    }
+
+    async fn simulate_register_upgrade_governor(
+        &self,
+        account: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().register_upgrade_governor(account),
+            options,
+        )
+        .await
+    }
+
+    fn build_register_upgrade_governor(&self, account: Address) -> TypedTransaction {
+        self.as_ref().register_upgrade_governor(account).tx
     }
 }
@@ -1,41 +1,29 @@
-use ethereum_instance::deploy_contract;
-use ethers::abi::{Token, Tokenize};
-use ethers::contract::ContractError;
+pub use ethereum_instance::{
+    bundled_artifact, ArtifactSource, BundledArtifact, ContractArtifact, DeployOptions, Error,
+};
+use ethereum_instance::{
+    create2_call_factory_address, deploy_contract_artifact_create2_call_with_receipt,
+    deploy_contract_artifact_create2_with_receipt, deploy_contract_artifact_with_receipt,
+};
+use ethers::abi::{AbiEncode, Token, Tokenize};
 use ethers::prelude::ContractInstance;
-use ethers::providers::ProviderError;
-use ethers::types::U256;
-use ethers::utils::hex::{self};
+use ethers::types::{Address, Bytes, TransactionReceipt, H256, U256};
 use std::cmp::PartialEq;
 use std::sync::Arc;
+use utils::errors::{ErrorContext, ResultExt};
+use utils::transaction::{send_call, TransactionOutcome};
 use utils::LocalWalletSignerMiddleware;
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    SerdeJson(#[from] serde_json::Error),
-    #[error("['bytecode']['object'] is not a string")]
-    BytecodeObject,
-    #[error(transparent)]
-    Hex(#[from] hex::FromHexError),
-    #[error("Failed to parse URL")]
-    UrlParser,
-    #[error(transparent)]
-    EthersContract(#[from] ContractError<LocalWalletSignerMiddleware>),
-    #[error(transparent)]
-    EthersProvider(#[from] ProviderError),
-    #[error("Invalid contract build artifacts: missing field `{0}`")]
-    ContractBuildArtifacts(&'static str),
-    #[error("Failed to deploy the contract : {0}")]
-    DeployContract(#[from] ethereum_instance::Error),
-}
+use crate::clients::proxy_3_0_2::{ProxyNominateNewGovernorCall, ProxySupport3_0_2};
+use crate::clients::proxy_5_0_0::{
+    ProxySupport5_0_0, RegisterGovernanceAdminCall, RegisterSecurityAdminCall,
+};
 
-const UNSAFE_PROXY: &str = include_str!("../../../../artifacts/UnsafeProxy.json");
-const SAFE_PROXY_3_0_2: &str =
-    include_str!("../../../../artifacts/starkgate-contracts-0.9/Proxy_3_0_2.json");
-const SAFE_PROXY_5_0_0: &str =
-    include_str!("../../../../artifacts/starkgate-contracts/Proxy_5_0_0.json");
+bundled_artifact!(pub(crate) UNSAFE_PROXY, "UnsafeProxy.json");
+bundled_artifact!(SAFE_PROXY_3_0_2, "starkgate-contracts-0.9/Proxy_3_0_2.json");
+bundled_artifact!(SAFE_PROXY_5_0_0, "starkgate-contracts/Proxy_5_0_0.json");
 
-#[derive(PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ProxyVersion {
     /// deploys unsafe proxy.
     UnsafeProxy,
@@ -45,11 +33,22 @@ pub enum ProxyVersion {
     SafeProxy5_0_0,
 }
 
+impl ProxyVersion {
+    fn contract_name(&self) -> &'static str {
+        match self {
+            ProxyVersion::UnsafeProxy => "UnsafeProxy",
+            ProxyVersion::SafeProxy3_0_2 => "Proxy_3_0_2",
+            ProxyVersion::SafeProxy5_0_0 => "Proxy_5_0_0",
+        }
+    }
+}
+
 pub async fn deploy_contract_behind_proxy<T: Tokenize>(
     client: Arc<LocalWalletSignerMiddleware>,
-    contract_path: &str,
+    artifact: &ContractArtifact,
     constructor_args: T,
     proxy_type: ProxyVersion,
+    options: &DeployOptions,
 ) -> Result<
     (
         ContractInstance<Arc<LocalWalletSignerMiddleware>, LocalWalletSignerMiddleware>,
@@ -57,21 +56,134 @@ pub async fn deploy_contract_behind_proxy<T: Tokenize>(
     ),
     Error,
 > {
-    let contract = deploy_contract(client.clone(), contract_path, constructor_args).await?;
+    let (contract, _) =
+        deploy_contract_artifact_with_receipt(client.clone(), artifact, constructor_args, options)
+            .await
+            .context(ErrorContext::new().method("deploy implementation"))?;
 
     log::debug!("ℹ️  Contract deployed : {:?}", contract.address().clone());
 
-    let proxy_code = match proxy_type {
+    let proxy_artifact = match proxy_type {
         ProxyVersion::SafeProxy3_0_2 => SAFE_PROXY_3_0_2,
         ProxyVersion::SafeProxy5_0_0 => SAFE_PROXY_5_0_0,
         ProxyVersion::UnsafeProxy => UNSAFE_PROXY,
     };
+    let proxy_artifact = options.artifact_source().load(&proxy_artifact)?;
 
-    let proxy_contract = match proxy_type {
+    let (proxy_contract, receipt) = match proxy_type {
         ProxyVersion::UnsafeProxy => {
-            deploy_contract(client.clone(), proxy_code, contract.address()).await?
+            deploy_contract_artifact_with_receipt(
+                client.clone(),
+                &proxy_artifact,
+                contract.address(),
+                options,
+            )
+            .await
+        }
+        _ => {
+            deploy_contract_artifact_with_receipt(
+                client.clone(),
+                &proxy_artifact,
+                Token::Uint(U256::from(0)),
+                options,
+            )
+            .await
+        }
+    }
+    .context(
+        ErrorContext::new()
+            .contract(proxy_type.contract_name())
+            .method("deploy"),
+    )?;
+
+    log::debug!(
+        "ℹ️  Proxy for contract [{:?}] deployed : {:?}",
+        contract.address().clone(),
+        proxy_contract.address()
+    );
+
+    record_proxy_deployment(
+        options,
+        artifact,
+        &proxy_type,
+        proxy_contract.address(),
+        contract.address(),
+        Some(&receipt),
+    )?;
+    Ok((proxy_contract, contract))
+}
+
+/// Same as [`deploy_contract_behind_proxy`] but both the implementation and the proxy
+/// are deployed with CREATE2 using `salt`, so that their addresses only depend on the
+/// build artifacts, the constructor args and the salt (and the deployer for safe proxies).
+/// Safe proxies grant governance to their deployer: they are deployed through the CREATE2
+/// call factory (see [`deploy_contract_artifact_create2_call_with_receipt`]) which hands
+/// governance over to the client account in the deployment transaction, the account then
+/// removing the factory from the governors. The hand-over is also completed for an already
+/// deployed proxy, e.g. if a previous run was interrupted before it was.
+pub async fn deploy_contract_behind_proxy_create2<T: Tokenize>(
+    client: Arc<LocalWalletSignerMiddleware>,
+    artifact: &ContractArtifact,
+    constructor_args: T,
+    proxy_type: ProxyVersion,
+    salt: H256,
+    options: &DeployOptions,
+) -> Result<
+    (
+        ContractInstance<Arc<LocalWalletSignerMiddleware>, LocalWalletSignerMiddleware>,
+        ContractInstance<Arc<LocalWalletSignerMiddleware>, LocalWalletSignerMiddleware>,
+    ),
+    Error,
+> {
+    let (contract, _) = deploy_contract_artifact_create2_with_receipt(
+        client.clone(),
+        artifact,
+        constructor_args,
+        salt,
+        options,
+    )
+    .await
+    .context(ErrorContext::new().method("deploy implementation"))?;
+
+    log::debug!("ℹ️  Contract deployed : {:?}", contract.address().clone());
+
+    let deploy_context = ErrorContext::new()
+        .contract(proxy_type.contract_name())
+        .method("deploy");
+    let (proxy_contract, receipt) = match proxy_type {
+        ProxyVersion::UnsafeProxy => {
+            let proxy_artifact = options.artifact_source().load(&UNSAFE_PROXY)?;
+            deploy_contract_artifact_create2_with_receipt(
+                client.clone(),
+                &proxy_artifact,
+                contract.address(),
+                salt,
+                options,
+            )
+            .await
+            .context(deploy_context)?
+        }
+        ProxyVersion::SafeProxy3_0_2 | ProxyVersion::SafeProxy5_0_0 => {
+            let proxy_artifact = if proxy_type == ProxyVersion::SafeProxy3_0_2 {
+                SAFE_PROXY_3_0_2
+            } else {
+                SAFE_PROXY_5_0_0
+            };
+            let proxy_artifact = options.artifact_source().load(&proxy_artifact)?;
+            let (proxy_contract, receipt) = deploy_contract_artifact_create2_call_with_receipt(
+                client.clone(),
+                &proxy_artifact,
+                Token::Uint(U256::from(0)),
+                salt,
+                &governance_handover_calls(proxy_type, client.address()),
+                options,
+            )
+            .await
+            .context(deploy_context)?;
+            complete_governance_handover(client.clone(), proxy_type, proxy_contract.address())
+                .await?;
+            (proxy_contract, receipt)
         }
-        _ => deploy_contract(client.clone(), proxy_code, Token::Uint(U256::from(0))).await?,
     };
 
     log::debug!(
@@ -80,5 +192,212 @@ pub async fn deploy_contract_behind_proxy<T: Tokenize>(
         proxy_contract.address()
     );
 
+    record_proxy_deployment(
+        options,
+        artifact,
+        &proxy_type,
+        proxy_contract.address(),
+        contract.address(),
+        receipt.as_ref(),
+    )?;
     Ok((proxy_contract, contract))
 }
+
+/// Calls sent by the CREATE2 call factory to a safe proxy it deployed, granting governance
+/// to `deployer`
+fn governance_handover_calls(proxy_type: ProxyVersion, deployer: Address) -> Vec<Bytes> {
+    match proxy_type {
+        ProxyVersion::UnsafeProxy => vec![],
+        ProxyVersion::SafeProxy3_0_2 => vec![ProxyNominateNewGovernorCall {
+            new_governor: deployer,
+        }
+        .encode()
+        .into()],
+        ProxyVersion::SafeProxy5_0_0 => vec![
+            RegisterGovernanceAdminCall { account: deployer }
+                .encode()
+                .into(),
+            RegisterSecurityAdminCall { account: deployer }
+                .encode()
+                .into(),
+        ],
+    }
+}
+
+/// Completes the governance hand-over of a safe proxy deployed through the CREATE2 call factory:
+/// the client account accepts governance if needed and revokes the roles of the factory.
+/// Only the missing steps are sent, so that it can be resumed on an already deployed proxy.
+async fn complete_governance_handover(
+    client: Arc<LocalWalletSignerMiddleware>,
+    proxy_type: ProxyVersion,
+    proxy_address: Address,
+) -> Result<(), Error> {
+    let account = client.address();
+    let factory = create2_call_factory_address();
+    let ensure_success = |outcome: TransactionOutcome, method: &str| {
+        outcome.into_receipt().map(drop).context(
+            ErrorContext::new()
+                .contract(proxy_type.contract_name())
+                .method(method)
+                .address(proxy_address),
+        )
+    };
+    let handover_error = |reason| Error::GovernanceHandover {
+        proxy: proxy_address,
+        account,
+        reason,
+    };
+
+    match proxy_type {
+        ProxyVersion::UnsafeProxy => {}
+        ProxyVersion::SafeProxy3_0_2 => {
+            let proxy = ProxySupport3_0_2::new(proxy_address, client);
+            if !proxy.proxy_is_governor(account).call().await? {
+                // Reverts unless `account` is nominated
+                match send_call(&proxy, proxy.proxy_accept_governance()).await {
+                    Err(err) if err.revert_reason().is_some() => {
+                        return Err(handover_error("it is neither governor nor nominated"));
+                    }
+                    outcome => ensure_success(outcome?, "proxyAcceptGovernance")?,
+                }
+            }
+            if proxy.proxy_is_governor(factory).call().await? {
+                ensure_success(
+                    send_call(&proxy, proxy.proxy_remove_governor(factory)).await?,
+                    "proxyRemoveGovernor",
+                )?;
+            }
+        }
+        ProxyVersion::SafeProxy5_0_0 => {
+            let proxy = ProxySupport5_0_0::new(proxy_address, client);
+            if !proxy.is_governance_admin(account).call().await? {
+                return Err(handover_error("it is not a governance admin"));
+            }
+            if proxy.is_security_admin(factory).call().await? {
+                ensure_success(
+                    send_call(&proxy, proxy.revoke_security_admin(factory)).await?,
+                    "revokeSecurityAdmin",
+                )?;
+            }
+            if proxy.is_governance_admin(factory).call().await? {
+                ensure_success(
+                    send_call(&proxy, proxy.revoke_governance_admin(factory)).await?,
+                    "revokeGovernanceAdmin",
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Records a contract deployed behind a proxy in the registry of the options,
+/// under the name of its artifacts (if any)
+fn record_proxy_deployment(
+    options: &DeployOptions,
+    artifact: &ContractArtifact,
+    proxy_type: &ProxyVersion,
+    proxy_address: Address,
+    implementation_address: Address,
+    proxy_receipt: Option<&TransactionReceipt>,
+) -> Result<(), Error> {
+    let Some(mut entry) = options.deployment_entry(artifact, proxy_address, proxy_receipt) else {
+        return Ok(());
+    };
+    entry.implementation = implementation_address;
+    entry.proxy_version = Some(proxy_type.contract_name().to_owned());
+    options.record(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethereum_instance::AnvilBuilder;
+
+    const IMPLEMENTATION: &str = r#"{
+        "abi": [],
+        "bytecode": { "object": "0x600a600c600039600a6000f3602a60005260206000f3" }
+    }"#;
+
+    #[tokio::test]
+    #[ignore = "requires anvil"]
+    async fn test_safe_proxies_create2_governance() {
+        let ethereum = AnvilBuilder::new().spawn().unwrap();
+        let client = ethereum.signer();
+        let deployer = client.address();
+        let factory = create2_call_factory_address();
+        // Anvil mines a block per transaction, only wait for inclusion
+        let options = DeployOptions::default().confirmations(1);
+        let implementation = ContractArtifact::parse(IMPLEMENTATION).unwrap();
+        let salt = H256::repeat_byte(0x02);
+
+        let (proxy, _) = deploy_contract_behind_proxy_create2(
+            client.clone(),
+            &implementation,
+            (),
+            ProxyVersion::SafeProxy3_0_2,
+            salt,
+            &options,
+        )
+        .await
+        .unwrap();
+        let proxy = ProxySupport3_0_2::new(proxy.address(), client.clone());
+        assert!(proxy.proxy_is_governor(deployer).call().await.unwrap());
+        assert!(!proxy.proxy_is_governor(factory).call().await.unwrap());
+
+        let (proxy, _) = deploy_contract_behind_proxy_create2(
+            client.clone(),
+            &implementation,
+            (),
+            ProxyVersion::SafeProxy5_0_0,
+            salt,
+            &options,
+        )
+        .await
+        .unwrap();
+        let proxy = ProxySupport5_0_0::new(proxy.address(), client);
+        assert!(proxy.is_governance_admin(deployer).call().await.unwrap());
+        assert!(!proxy.is_governance_admin(factory).call().await.unwrap());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires anvil"]
+    async fn test_resume_interrupted_governance_handover() {
+        let ethereum = AnvilBuilder::new().spawn().unwrap();
+        let client = ethereum.signer();
+        let deployer = client.address();
+        let factory = create2_call_factory_address();
+        // Anvil mines a block per transaction, only wait for inclusion
+        let options = DeployOptions::default().confirmations(1);
+        let implementation = ContractArtifact::parse(IMPLEMENTATION).unwrap();
+        let salt = H256::repeat_byte(0x03);
+
+        // Deployed and nominated by the factory, the deployer never accepting governance
+        let proxy_artifact = options.artifact_source().load(&SAFE_PROXY_3_0_2).unwrap();
+        let (proxy, _) = deploy_contract_artifact_create2_call_with_receipt(
+            client.clone(),
+            &proxy_artifact,
+            Token::Uint(U256::from(0)),
+            salt,
+            &governance_handover_calls(ProxyVersion::SafeProxy3_0_2, deployer),
+            &options,
+        )
+        .await
+        .unwrap();
+        let proxy = ProxySupport3_0_2::new(proxy.address(), client.clone());
+        assert!(!proxy.proxy_is_governor(deployer).call().await.unwrap());
+
+        let (resumed, _) = deploy_contract_behind_proxy_create2(
+            client.clone(),
+            &implementation,
+            (),
+            ProxyVersion::SafeProxy3_0_2,
+            salt,
+            &options,
+        )
+        .await
+        .unwrap();
+        assert_eq!(resumed.address(), proxy.address());
+        assert!(proxy.proxy_is_governor(deployer).call().await.unwrap());
+        assert!(!proxy.proxy_is_governor(factory).call().await.unwrap());
+    }
+}
@@ -0,0 +1,267 @@
+use ethers::contract::ContractError;
+use ethers::middleware::Middleware;
+use ethers::types::{Address, Bytes, H256};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use utils::errors::Error;
+
+use crate::clients::proxy_5_0_0::ProxySupport5_0_0;
+use crate::deploy::{ArtifactSource, UNSAFE_PROXY};
+
+/// `bytes32(uint256(keccak256("eip1967.proxy.implementation")) - 1)`
+pub const EIP1967_IMPLEMENTATION_SLOT: H256 = H256([
+    0x36, 0x08, 0x94, 0xa1, 0x3b, 0xa1, 0xa3, 0x21, 0x06, 0x67, 0xc8, 0x28, 0x49, 0x2d, 0xb9, 0x8d,
+    0xca, 0x3e, 0x20, 0x76, 0xcc, 0x37, 0x35, 0xa9, 0x20, 0xa3, 0xca, 0x50, 0x5d, 0x38, 0x2b, 0xbc,
+]);
+
+/// Reads the current implementation of a proxy: the delegate (slot 0) of an
+/// [`crate::deploy::ProxyVersion::UnsafeProxy`], recognized by its runtime bytecode taken from
+/// the embedded artifacts (see [`resolve_implementation_with_artifacts`] otherwise), then
+/// `implementation()` of the StarkWare proxies (3.0.2 and 5.0.0 share it), then the EIP-1967
+/// implementation slot. The unsafe proxy is checked first as it forwards `implementation()`
+/// to its delegate. Fails with [`Error::NoImplementation`] if the proxy has no implementation
+/// yet, with [`Error::ImplementationWithoutCode`] if there is no contract at the implementation
+/// address and with [`Error::NotAProxy`] if no implementation is found.
+pub async fn resolve_implementation<M: Middleware>(
+    proxy_address: Address,
+    client: Arc<M>,
+) -> Result<Address, Error<M>> {
+    resolve_implementation_with_artifacts(proxy_address, client, &ArtifactSource::embedded()).await
+}
+
+/// Same as [`resolve_implementation`] with the `UnsafeProxy` artifact loaded from `artifacts`
+pub async fn resolve_implementation_with_artifacts<M: Middleware>(
+    proxy_address: Address,
+    client: Arc<M>,
+    artifacts: &ArtifactSource,
+) -> Result<Address, Error<M>> {
+    let code = get_code(&client, proxy_address).await?;
+    if code.is_empty() {
+        return Err(Error::NotAProxy(proxy_address));
+    }
+
+    let implementation = if is_unsafe_proxy(&code, artifacts) {
+        read_address(&client, proxy_address, H256::zero()).await?
+    } else {
+        let proxy = ProxySupport5_0_0::new(proxy_address, client.clone());
+        match proxy.implementation().call().await {
+            Ok(implementation) => implementation,
+            Err(
+                err @ (ContractError::MiddlewareError { .. } | ContractError::ProviderError { .. }),
+            ) => return Err(err.into()),
+            // Reverted or returned nothing: not a StarkWare proxy
+            Err(_) => {
+                let implementation =
+                    read_address(&client, proxy_address, EIP1967_IMPLEMENTATION_SLOT).await?;
+                if implementation.is_zero() {
+                    return Err(Error::NotAProxy(proxy_address));
+                }
+                implementation
+            }
+        }
+    };
+
+    if implementation.is_zero() {
+        return Err(Error::NoImplementation(proxy_address));
+    }
+    if get_code(&client, implementation).await?.is_empty() {
+        return Err(Error::ImplementationWithoutCode {
+            proxy: proxy_address,
+            implementation,
+        });
+    }
+    Ok(implementation)
+}
+
+/// Whether `code` is the runtime bytecode of the `UnsafeProxy`, `false` if its artifact
+/// can't be loaded
+fn is_unsafe_proxy(code: &Bytes, artifacts: &ArtifactSource) -> bool {
+    match artifacts
+        .load(&UNSAFE_PROXY)
+        .and_then(|artifact| artifact.deployed_bytecode(&BTreeMap::new()))
+    {
+        Ok(runtime_bytecode) => *code == runtime_bytecode,
+        Err(err) => {
+            log::debug!("ℹ️  UnsafeProxy runtime bytecode unavailable: {}", err);
+            false
+        }
+    }
+}
+
+async fn get_code<M: Middleware>(client: &Arc<M>, address: Address) -> Result<Bytes, Error<M>> {
+    client
+        .get_code(address, None)
+        .await
+        .map_err(|err| ContractError::from_middleware_error(err).into())
+}
+
+/// Address stored in the lower 20 bytes of a storage slot
+async fn read_address<M: Middleware>(
+    client: &Arc<M>,
+    address: Address,
+    slot: H256,
+) -> Result<Address, Error<M>> {
+    let value = client
+        .get_storage_at(address, slot, None)
+        .await
+        .map_err(ContractError::from_middleware_error)?;
+    Ok(Address::from_slice(&value.as_bytes()[12..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clients::proxy_3_0_2::ProxySupport3_0_2;
+    use crate::deploy::{
+        deploy_contract_behind_proxy, ContractArtifact, DeployOptions, ProxyVersion,
+    };
+    use ethereum_instance::AnvilBuilder;
+    use ethers::types::{Bytes, U256};
+    use ethers::utils::keccak256;
+    use utils::transaction::send_call;
+
+    const IMPLEMENTATION: &str = r#"{
+        "abi": [],
+        "bytecode": { "object": "0x600a600c600039600a6000f3602a60005260206000f3" }
+    }"#;
+
+    #[test]
+    fn test_eip1967_implementation_slot() {
+        let slot = U256::from(keccak256("eip1967.proxy.implementation")) - 1;
+        assert_eq!(
+            EIP1967_IMPLEMENTATION_SLOT,
+            H256::from(<[u8; 32]>::from(slot))
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "requires anvil"]
+    async fn test_resolve_unsafe_proxy_implementation() {
+        let ethereum = AnvilBuilder::new().spawn().unwrap();
+        let client = ethereum.signer();
+        // Anvil mines a block per transaction, only wait for inclusion
+        let options = DeployOptions::default().confirmations(1);
+        // Returns 42 to anything, `implementation()` forwarded by the proxy included
+        let artifact = ContractArtifact::parse(IMPLEMENTATION).unwrap();
+
+        let (proxy, implementation) = deploy_contract_behind_proxy(
+            client.clone(),
+            &artifact,
+            (),
+            ProxyVersion::UnsafeProxy,
+            &options,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            resolve_implementation(proxy.address(), client.clone())
+                .await
+                .unwrap(),
+            implementation.address()
+        );
+        assert!(matches!(
+            resolve_implementation(implementation.address(), client.clone()).await,
+            Err(Error::ImplementationWithoutCode { .. })
+        ));
+        assert!(matches!(
+            resolve_implementation(Address::repeat_byte(0x11), client).await,
+            Err(Error::NotAProxy(_))
+        ));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires anvil"]
+    async fn test_resolve_safe_proxy_3_0_2_implementation() {
+        let ethereum = AnvilBuilder::new().spawn().unwrap();
+        let client = ethereum.signer();
+        // Anvil mines a block per transaction, only wait for inclusion
+        let options = DeployOptions::default().confirmations(1);
+        let artifact = ContractArtifact::parse(IMPLEMENTATION).unwrap();
+
+        // Safe proxies are deployed without implementation
+        let (proxy, implementation) = deploy_contract_behind_proxy(
+            client.clone(),
+            &artifact,
+            (),
+            ProxyVersion::SafeProxy3_0_2,
+            &options,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(
+            resolve_implementation(proxy.address(), client.clone()).await,
+            Err(Error::NoImplementation(_))
+        ));
+
+        let proxy = ProxySupport3_0_2::new(proxy.address(), client.clone());
+        let (address, data) = (implementation.address(), Bytes::new());
+        assert!(send_call(
+            &proxy,
+            proxy.add_implementation(address, data.clone(), false)
+        )
+        .await
+        .unwrap()
+        .is_success());
+        assert!(send_call(&proxy, proxy.upgrade_to(address, data, false))
+            .await
+            .unwrap()
+            .is_success());
+        assert_eq!(
+            resolve_implementation(proxy.address(), client)
+                .await
+                .unwrap(),
+            address
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "requires anvil"]
+    async fn test_resolve_safe_proxy_5_0_0_implementation() {
+        let ethereum = AnvilBuilder::new().spawn().unwrap();
+        let client = ethereum.signer();
+        // Anvil mines a block per transaction, only wait for inclusion
+        let options = DeployOptions::default().confirmations(1);
+        let artifact = ContractArtifact::parse(IMPLEMENTATION).unwrap();
+
+        // Safe proxies are deployed without implementation
+        let (proxy, implementation) = deploy_contract_behind_proxy(
+            client.clone(),
+            &artifact,
+            (),
+            ProxyVersion::SafeProxy5_0_0,
+            &options,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(
+            resolve_implementation(proxy.address(), client.clone()).await,
+            Err(Error::NoImplementation(_))
+        ));
+
+        let proxy = ProxySupport5_0_0::new(proxy.address(), client.clone());
+        let (address, data) = (implementation.address(), Bytes::new());
+        assert!(
+            send_call(&proxy, proxy.register_upgrade_governor(client.address()))
+                .await
+                .unwrap()
+                .is_success()
+        );
+        assert!(send_call(
+            &proxy,
+            proxy.add_implementation(address, data.clone(), false)
+        )
+        .await
+        .unwrap()
+        .is_success());
+        assert!(send_call(&proxy, proxy.upgrade_to(address, data, false))
+            .await
+            .unwrap()
+            .is_success());
+        assert_eq!(
+            resolve_implementation(proxy.address(), client)
+                .await
+                .unwrap(),
+            address
+        );
+    }
+}
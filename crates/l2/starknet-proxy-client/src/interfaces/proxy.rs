@@ -3,98 +3,251 @@ use ethers::abi::AbiEncode;
 use ethers::addressbook::Address;
 use ethers::contract::{EthAbiCodec, EthAbiType};
 use ethers::middleware::Middleware;
-use ethers::prelude::{Bytes, TransactionReceipt, I256, U256};
+use ethers::prelude::{Bytes, I256, U256};
+use ethers::types::transaction::eip2718::TypedTransaction;
 use utils::errors::Error;
+use utils::transaction::{SimulationOptions, SimulationOutcome, TransactionOutcome};
 
 #[async_trait]
 pub trait ProxySupport3_0_2Trait<M: Middleware> {
-    async fn initialize(&self, data: Bytes) -> Result<Option<TransactionReceipt>, Error<M>>;
+    async fn initialize(&self, data: Bytes) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_initialize(
+        &self,
+        data: Bytes,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_initialize(&self, data: Bytes) -> TypedTransaction;
     async fn initialize_with<const N: usize>(
         &self,
         data: ProxyInitializeData<N>,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_initialize_with<const N: usize>(
+        &self,
+        data: ProxyInitializeData<N>,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_initialize_with<const N: usize>(
+        &self,
+        data: ProxyInitializeData<N>,
+    ) -> TypedTransaction;
     async fn upgrade_to(
         &self,
         data: Bytes,
         implementation_address: Address,
         finalized: bool,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_upgrade_to(
+        &self,
+        data: Bytes,
+        implementation_address: Address,
+        finalized: bool,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_upgrade_to(
+        &self,
+        data: Bytes,
+        implementation_address: Address,
+        finalized: bool,
+    ) -> TypedTransaction;
     async fn add_implementation(
         &self,
         data: Bytes,
         implementation_address: Address,
         finalized: bool,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_add_implementation(
+        &self,
+        data: Bytes,
+        implementation_address: Address,
+        finalized: bool,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_add_implementation(
+        &self,
+        data: Bytes,
+        implementation_address: Address,
+        finalized: bool,
+    ) -> TypedTransaction;
     async fn proxy_nominate_new_governor(
         &self,
         new_governor: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_proxy_nominate_new_governor(
+        &self,
+        new_governor: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_proxy_nominate_new_governor(&self, new_governor: Address) -> TypedTransaction;
     async fn proxy_remove_governance(
         &self,
         governor: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
-    async fn proxy_accept_governance(&self) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_proxy_remove_governance(
+        &self,
+        governor: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_proxy_remove_governance(&self, governor: Address) -> TypedTransaction;
+    async fn proxy_accept_governance(&self) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_proxy_accept_governance(
+        &self,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_proxy_accept_governance(&self) -> TypedTransaction;
 }
 
 #[async_trait]
 pub trait ProxySupport5_0_0Trait<M: Middleware> {
-    async fn initialize(&self, data: Bytes) -> Result<Option<TransactionReceipt>, Error<M>>;
+    async fn initialize(&self, data: Bytes) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_initialize(
+        &self,
+        data: Bytes,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_initialize(&self, data: Bytes) -> TypedTransaction;
     async fn initialize_with<const N: usize>(
         &self,
         data: ProxyInitializeData<N>,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_initialize_with<const N: usize>(
+        &self,
+        data: ProxyInitializeData<N>,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_initialize_with<const N: usize>(
+        &self,
+        data: ProxyInitializeData<N>,
+    ) -> TypedTransaction;
     async fn upgrade_to(
         &self,
         data: Bytes,
         implementation_address: Address,
         finalized: bool,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_upgrade_to(
+        &self,
+        data: Bytes,
+        implementation_address: Address,
+        finalized: bool,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_upgrade_to(
+        &self,
+        data: Bytes,
+        implementation_address: Address,
+        finalized: bool,
+    ) -> TypedTransaction;
     async fn add_implementation(
         &self,
         data: Bytes,
         implementation_address: Address,
         finalized: bool,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_add_implementation(
+        &self,
+        data: Bytes,
+        implementation_address: Address,
+        finalized: bool,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_add_implementation(
+        &self,
+        data: Bytes,
+        implementation_address: Address,
+        finalized: bool,
+    ) -> TypedTransaction;
     async fn proxy_nominate_new_governor(
         &self,
         new_governor: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_proxy_nominate_new_governor(
+        &self,
+        new_governor: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_proxy_nominate_new_governor(&self, new_governor: Address) -> TypedTransaction;
     async fn proxy_remove_governance(
         &self,
         governor: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
-    async fn register_app_governor(
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_proxy_remove_governance(
+        &self,
+        governor: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_proxy_remove_governance(&self, governor: Address) -> TypedTransaction;
+    async fn register_app_governor(&self, account: Address)
+        -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_register_app_governor(
         &self,
         account: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_register_app_governor(&self, account: Address) -> TypedTransaction;
     async fn register_app_role_admin(
         &self,
         account: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_register_app_role_admin(
+        &self,
+        account: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_register_app_role_admin(&self, account: Address) -> TypedTransaction;
     async fn register_governance_admin(
         &self,
         account: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
-    async fn register_operator(
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_register_governance_admin(
         &self,
         account: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_register_governance_admin(&self, account: Address) -> TypedTransaction;
+    async fn register_operator(&self, account: Address) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_register_operator(
+        &self,
+        account: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_register_operator(&self, account: Address) -> TypedTransaction;
     async fn register_security_admin(
         &self,
         account: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_register_security_admin(
+        &self,
+        account: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_register_security_admin(&self, account: Address) -> TypedTransaction;
     async fn register_security_agent(
         &self,
         account: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
-    async fn register_token_admin(
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_register_security_agent(
         &self,
         account: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_register_security_agent(&self, account: Address) -> TypedTransaction;
+    async fn register_token_admin(&self, account: Address) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_register_token_admin(
+        &self,
+        account: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_register_token_admin(&self, account: Address) -> TypedTransaction;
     async fn register_upgrade_governor(
         &self,
         account: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_register_upgrade_governor(
+        &self,
+        account: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_register_upgrade_governor(&self, account: Address) -> TypedTransaction;
 }
 
 #[derive(Debug, Clone, Default, PartialEq, EthAbiType, EthAbiCodec)]
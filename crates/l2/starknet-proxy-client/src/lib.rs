@@ -1,3 +1,4 @@
 pub mod clients;
 pub mod deploy;
+pub mod implementation;
 pub mod interfaces;
@@ -1,46 +1,69 @@
 use std::sync::Arc;
 
 use crate::interfaces::token_bridge::StarknetTokenBridge;
-use utils::{LocalWalletSignerMiddleware, StarknetContractClient};
+use starknet_proxy_client::implementation::resolve_implementation;
+use utils::errors::Error;
+use utils::registry::DeploymentEntry;
+use utils::{LocalWalletSignerMiddleware, ReadOnly, ReadWrite, StarknetContractClient};
 
+use ethers::middleware::Middleware;
 use ethers::types::Address;
 use starknet_proxy_client::clients::proxy_5_0_0::ProxySupport5_0_0;
 
 /// Client to interact with a Token Bridge (ERC20)
 #[derive(Clone)]
-pub struct StarknetTokenBridgeContractClient {
-    token_bridge: StarknetTokenBridge<LocalWalletSignerMiddleware>,
-    proxy_support: ProxySupport5_0_0<LocalWalletSignerMiddleware>,
+pub struct StarknetTokenBridgeContractClient<M = LocalWalletSignerMiddleware> {
+    token_bridge: StarknetTokenBridge<M>,
+    proxy_support: ProxySupport5_0_0<M>,
     token_bridge_implementation: Address,
 }
 
-impl StarknetTokenBridgeContractClient {
-    pub fn new(
-        address: Address,
-        client: Arc<LocalWalletSignerMiddleware>,
-        implementation_address: Address,
-    ) -> Self {
+impl<M: Middleware> StarknetTokenBridgeContractClient<M> {
+    pub fn new(address: Address, client: Arc<M>, implementation_address: Address) -> Self {
         Self {
             token_bridge: StarknetTokenBridge::new(address, client.clone()),
             proxy_support: ProxySupport5_0_0::new(address, client.clone()),
             token_bridge_implementation: implementation_address,
         }
     }
+
+    /// Client of a deployment behind a proxy, its implementation being read on-chain
+    /// (see [`resolve_implementation`])
+    pub async fn attach(address: Address, client: Arc<M>) -> Result<Self, Error<M>> {
+        let implementation_address = resolve_implementation(address, client.clone()).await?;
+        Ok(Self::new(address, client, implementation_address))
+    }
+
+    /// Client of a deployment recorded in a [`utils::registry::DeploymentRegistry`]
+    pub fn from_registry_entry(entry: &DeploymentEntry, client: Arc<M>) -> Self {
+        Self::new(entry.address, client, entry.implementation)
+    }
+
+    /// Read-only client, typically over a plain `Provider`: only view methods can be called.
+    pub fn read_only(
+        address: Address,
+        client: Arc<M>,
+        implementation_address: Address,
+    ) -> ReadOnly<Self> {
+        ReadOnly::new(Self::new(address, client, implementation_address))
+    }
 }
 
-impl AsRef<StarknetTokenBridge<LocalWalletSignerMiddleware>> for StarknetTokenBridgeContractClient {
-    fn as_ref(&self) -> &StarknetTokenBridge<LocalWalletSignerMiddleware> {
+impl<M> AsRef<StarknetTokenBridge<M>> for StarknetTokenBridgeContractClient<M> {
+    fn as_ref(&self) -> &StarknetTokenBridge<M> {
         &self.token_bridge
     }
 }
 
-impl AsRef<ProxySupport5_0_0<LocalWalletSignerMiddleware>> for StarknetTokenBridgeContractClient {
-    fn as_ref(&self) -> &ProxySupport5_0_0<LocalWalletSignerMiddleware> {
+impl<M> AsRef<ProxySupport5_0_0<M>> for StarknetTokenBridgeContractClient<M> {
+    fn as_ref(&self) -> &ProxySupport5_0_0<M> {
         &self.proxy_support
     }
 }
 
-impl StarknetContractClient for StarknetTokenBridgeContractClient {
+impl<M> ReadWrite for StarknetTokenBridgeContractClient<M> {}
+
+impl<M: Middleware> StarknetContractClient<M> for StarknetTokenBridgeContractClient<M> {
     fn address(&self) -> ethers::abi::Address {
         self.token_bridge.address()
     }
@@ -49,7 +72,7 @@ impl StarknetContractClient for StarknetTokenBridgeContractClient {
         self.token_bridge_implementation
     }
 
-    fn client(&self) -> Arc<LocalWalletSignerMiddleware> {
+    fn client(&self) -> Arc<M> {
         self.token_bridge.client()
     }
 }
@@ -1,19 +1,21 @@
 use async_trait::async_trait;
+use ethers::contract::ContractCall;
+use ethers::types::transaction::eip2718::TypedTransaction;
 use ethers::{
-    contract::ContractError,
     prelude::abigen,
     providers::Middleware,
-    types::{TransactionReceipt, H160, U256},
+    types::{H160, U256},
 };
 
 use utils::errors::Error;
+use utils::transaction::{
+    send_call, simulate_call, SimulationOptions, SimulationOutcome, TransactionOutcome,
+};
+use utils::ContractReader;
 
 type Address = H160;
 
-abigen!(
-    StarknetTokenBridge,
-    "../../../artifacts/starkgate-contracts/StarknetTokenBridge.json",
-);
+abigen!(StarknetTokenBridge, "abi/StarknetTokenBridge.json");
 
 #[async_trait]
 pub trait StarknetTokenBridgeTrait<M: Middleware> {
@@ -23,39 +25,83 @@ pub trait StarknetTokenBridgeTrait<M: Middleware> {
         amount: U256,
         l2_recipient: U256,
         fee: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_deposit(
+        &self,
+        token: Address,
+        amount: U256,
+        l2_recipient: U256,
+        fee: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_deposit(
+        &self,
+        token: Address,
+        amount: U256,
+        l2_recipient: U256,
+        fee: U256,
+    ) -> TypedTransaction;
     async fn withdraw(
         &self,
         token: Address,
         amount: U256,
         recipient: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_withdraw(
+        &self,
+        token: Address,
+        amount: U256,
+        recipient: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_withdraw(&self, token: Address, amount: U256, recipient: Address) -> TypedTransaction;
     async fn set_l2_token_bridge(
         &self,
         l2_token_bridge: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_set_l2_token_bridge(
+        &self,
+        l2_token_bridge: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_set_l2_token_bridge(&self, l2_token_bridge: U256) -> TypedTransaction;
 
     async fn enable_withdrawal_limit(
         &self,
         address: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_enable_withdrawal_limit(
+        &self,
+        address: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_enable_withdrawal_limit(&self, address: Address) -> TypedTransaction;
     async fn disable_withdrawal_limit(
         &self,
         address: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_disable_withdrawal_limit(
+        &self,
+        address: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_disable_withdrawal_limit(&self, address: Address) -> TypedTransaction;
     async fn set_max_total_balance(
         &self,
         token: Address,
         max_total_balance: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>>;
-
-    async fn identify(&self) -> Result<String, Error<M>>;
-    async fn estimate_deposit_fee_wei(&self) -> Result<U256, Error<M>>;
-    async fn estimate_enrollment_fee_wei(&self) -> Result<U256, Error<M>>;
-    async fn is_servicing_token(&self, token: Address) -> Result<bool, Error<M>>;
-    async fn get_remaining_intraday_allowance(&self, token: Address) -> Result<U256, Error<M>>;
-    async fn get_max_total_balance(&self, token: Address) -> Result<U256, Error<M>>;
-    async fn max_deposit(&self) -> Result<U256, Error<M>>;
+    ) -> Result<TransactionOutcome, Error<M>>;
+    async fn simulate_set_max_total_balance(
+        &self,
+        token: Address,
+        max_total_balance: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>>;
+    fn build_set_max_total_balance(
+        &self,
+        token: Address,
+        max_total_balance: U256,
+    ) -> TypedTransaction;
 }
 
 #[async_trait]
@@ -69,15 +115,45 @@ where
         amount: U256,
         l2_recipient: U256,
         fee: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref()
+                .deposit(token, amount, l2_recipient)
+                .value(fee),
+        )
+        .await
+    }
+
+    async fn simulate_deposit(
+        &self,
+        token: Address,
+        amount: U256,
+        l2_recipient: U256,
+        fee: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref()
+                .deposit(token, amount, l2_recipient)
+                .value(fee),
+            options,
+        )
+        .await
+    }
+
+    fn build_deposit(
+        &self,
+        token: Address,
+        amount: U256,
+        l2_recipient: U256,
+        fee: U256,
+    ) -> TypedTransaction {
         self.as_ref()
             .deposit(token, amount, l2_recipient)
             .value(fee)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+            .tx
     }
 
     async fn withdraw(
@@ -85,114 +161,246 @@ where
         token: Address,
         amount: U256,
         _recipient: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .withdraw(token, amount)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(self.as_ref(), self.as_ref().withdraw(token, amount)).await
+    }
+
+    async fn simulate_withdraw(
+        &self,
+        token: Address,
+        amount: U256,
+        _recipient: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().withdraw(token, amount),
+            options,
+        )
+        .await
+    }
+
+    fn build_withdraw(
+        &self,
+        token: Address,
+        amount: U256,
+        _recipient: Address,
+    ) -> TypedTransaction {
+        self.as_ref().withdraw(token, amount).tx
     }
 
     async fn set_l2_token_bridge(
         &self,
         l2_token_bridge: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .set_l2_token_bridge(l2_token_bridge)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref().set_l2_token_bridge(l2_token_bridge),
+        )
+        .await
+    }
+
+    async fn simulate_set_l2_token_bridge(
+        &self,
+        l2_token_bridge: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().set_l2_token_bridge(l2_token_bridge),
+            options,
+        )
+        .await
+    }
+
+    fn build_set_l2_token_bridge(&self, l2_token_bridge: U256) -> TypedTransaction {
+        self.as_ref().set_l2_token_bridge(l2_token_bridge).tx
     }
 
     async fn enable_withdrawal_limit(
         &self,
         token: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .enable_withdrawal_limit(token)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(self.as_ref(), self.as_ref().enable_withdrawal_limit(token)).await
+    }
+
+    async fn simulate_enable_withdrawal_limit(
+        &self,
+        token: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().enable_withdrawal_limit(token),
+            options,
+        )
+        .await
+    }
+
+    fn build_enable_withdrawal_limit(&self, token: Address) -> TypedTransaction {
+        self.as_ref().enable_withdrawal_limit(token).tx
     }
 
     async fn disable_withdrawal_limit(
         &self,
         token: Address,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
-        self.as_ref()
-            .disable_withdrawal_limit(token)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(self.as_ref(), self.as_ref().disable_withdrawal_limit(token)).await
+    }
+
+    async fn simulate_disable_withdrawal_limit(
+        &self,
+        token: Address,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref().disable_withdrawal_limit(token),
+            options,
+        )
+        .await
+    }
+
+    fn build_disable_withdrawal_limit(&self, token: Address) -> TypedTransaction {
+        self.as_ref().disable_withdrawal_limit(token).tx
     }
 
     async fn set_max_total_balance(
         &self,
         token: Address,
         max_total_balance: U256,
-    ) -> Result<Option<TransactionReceipt>, Error<M>> {
+    ) -> Result<TransactionOutcome, Error<M>> {
+        send_call(
+            self.as_ref(),
+            self.as_ref()
+                .set_max_total_balance(token, max_total_balance),
+        )
+        .await
+    }
+
+    async fn simulate_set_max_total_balance(
+        &self,
+        token: Address,
+        max_total_balance: U256,
+        options: &SimulationOptions,
+    ) -> Result<SimulationOutcome<()>, Error<M>> {
+        simulate_call(
+            self.as_ref(),
+            self.as_ref()
+                .set_max_total_balance(token, max_total_balance),
+            options,
+        )
+        .await
+    }
+
+    fn build_set_max_total_balance(
+        &self,
+        token: Address,
+        max_total_balance: U256,
+    ) -> TypedTransaction {
         self.as_ref()
             .set_max_total_balance(token, max_total_balance)
-            .send()
-            .await
-            .map_err(Into::<ContractError<M>>::into)?
-            .await
-            .map_err(Into::into)
+            .tx
     }
+}
+
+/// View methods of [`StarknetTokenBridgeTrait`]
+#[async_trait]
+pub trait StarknetTokenBridgeViewTrait<M: Middleware> {
+    async fn identify(&self) -> Result<String, Error<M>>;
+    fn identify_call(&self) -> ContractCall<M, String>;
+    async fn estimate_deposit_fee_wei(&self) -> Result<U256, Error<M>>;
+    fn estimate_deposit_fee_wei_call(&self) -> ContractCall<M, U256>;
+    async fn estimate_enrollment_fee_wei(&self) -> Result<U256, Error<M>>;
+    fn estimate_enrollment_fee_wei_call(&self) -> ContractCall<M, U256>;
+    async fn is_servicing_token(&self, token: Address) -> Result<bool, Error<M>>;
+    fn is_servicing_token_call(&self, token: Address) -> ContractCall<M, bool>;
+    async fn get_remaining_intraday_allowance(&self, token: Address) -> Result<U256, Error<M>>;
+    fn get_remaining_intraday_allowance_call(&self, token: Address) -> ContractCall<M, U256>;
+    async fn get_max_total_balance(&self, token: Address) -> Result<U256, Error<M>>;
+    fn get_max_total_balance_call(&self, token: Address) -> ContractCall<M, U256>;
+    async fn max_deposit(&self) -> Result<U256, Error<M>>;
+    fn max_deposit_call(&self) -> ContractCall<M, U256>;
+}
 
+#[async_trait]
+impl<T, M: Middleware> StarknetTokenBridgeViewTrait<M> for T
+where
+    T: ContractReader<StarknetTokenBridge<M>> + Send + Sync,
+{
     async fn identify(&self) -> Result<String, Error<M>> {
-        self.as_ref().identify().call().await.map_err(Into::into)
+        self.reader().identify().call().await.map_err(Into::into)
+    }
+
+    fn identify_call(&self) -> ContractCall<M, String> {
+        self.reader().identify()
     }
 
     async fn estimate_deposit_fee_wei(&self) -> Result<U256, Error<M>> {
-        self.as_ref()
+        self.reader()
             .estimate_deposit_fee_wei()
             .call()
             .await
             .map_err(Into::into)
     }
 
+    fn estimate_deposit_fee_wei_call(&self) -> ContractCall<M, U256> {
+        self.reader().estimate_deposit_fee_wei()
+    }
+
     async fn estimate_enrollment_fee_wei(&self) -> Result<U256, Error<M>> {
-        self.as_ref()
+        self.reader()
             .estimate_enrollment_fee_wei()
             .call()
             .await
             .map_err(Into::into)
     }
 
+    fn estimate_enrollment_fee_wei_call(&self) -> ContractCall<M, U256> {
+        self.reader().estimate_enrollment_fee_wei()
+    }
+
     async fn is_servicing_token(&self, token: Address) -> Result<bool, Error<M>> {
-        self.as_ref()
+        self.reader()
             .is_servicing_token(token)
             .call()
             .await
             .map_err(Into::into)
     }
 
+    fn is_servicing_token_call(&self, token: Address) -> ContractCall<M, bool> {
+        self.reader().is_servicing_token(token)
+    }
+
     async fn get_remaining_intraday_allowance(&self, token: Address) -> Result<U256, Error<M>> {
-        self.as_ref()
+        self.reader()
             .get_remaining_intraday_allowance(token)
             .call()
             .await
             .map_err(Into::into)
     }
 
+    fn get_remaining_intraday_allowance_call(&self, token: Address) -> ContractCall<M, U256> {
+        self.reader().get_remaining_intraday_allowance(token)
+    }
+
     async fn get_max_total_balance(&self, token: Address) -> Result<U256, Error<M>> {
-        self.as_ref()
+        self.reader()
             .get_max_total_balance(token)
             .call()
             .await
             .map_err(Into::into)
     }
 
+    fn get_max_total_balance_call(&self, token: Address) -> ContractCall<M, U256> {
+        self.reader().get_max_total_balance(token)
+    }
+
     async fn max_deposit(&self) -> Result<U256, Error<M>> {
-        self.as_ref().max_deposit().call().await.map_err(Into::into)
+        self.reader().max_deposit().call().await.map_err(Into::into)
+    }
+
+    fn max_deposit_call(&self) -> ContractCall<M, U256> {
+        self.reader().max_deposit()
     }
 }
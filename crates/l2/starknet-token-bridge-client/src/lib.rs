@@ -1,26 +1,34 @@
 use std::sync::Arc;
 
 use clients::token_bridge::StarknetTokenBridgeContractClient;
-use starknet_proxy_client::deploy::{deploy_contract_behind_proxy, Error, ProxyVersion};
+use starknet_proxy_client::deploy::{
+    bundled_artifact, deploy_contract_behind_proxy, DeployOptions, Error, ProxyVersion,
+};
+use utils::errors::{ErrorContext, ResultExt};
 use utils::{LocalWalletSignerMiddleware, NO_CONSTRUCTOR_ARG};
 
 pub mod clients;
 pub mod interfaces;
 
-const STARKNET_TOKEN_BRIDGE: &str =
-    include_str!("../../../../artifacts/starkgate-contracts/StarknetTokenBridge.json");
+bundled_artifact!(
+    STARKNET_TOKEN_BRIDGE,
+    "starkgate-contracts/StarknetTokenBridge.json"
+);
 
 pub async fn deploy_starknet_token_bridge_behind_unsafe_proxy(
     client: Arc<LocalWalletSignerMiddleware>,
+    options: &DeployOptions,
 ) -> Result<StarknetTokenBridgeContractClient, Error> {
     // Deploy the Starknet Token Bridge contract (no explicit constructor)
     let token_bridge_contract = deploy_contract_behind_proxy(
         client.clone(),
-        STARKNET_TOKEN_BRIDGE,
+        &options.artifact_source().load(&STARKNET_TOKEN_BRIDGE)?,
         NO_CONSTRUCTOR_ARG,
         ProxyVersion::UnsafeProxy,
+        options,
     )
-    .await?;
+    .await
+    .context(ErrorContext::new().contract("StarknetTokenBridge"))?;
 
     Ok(StarknetTokenBridgeContractClient::new(
         token_bridge_contract.0.address(),
@@ -31,16 +39,19 @@ pub async fn deploy_starknet_token_bridge_behind_unsafe_proxy(
 
 pub async fn deploy_starknet_token_bridge_behind_safe_proxy(
     client: Arc<LocalWalletSignerMiddleware>,
+    options: &DeployOptions,
 ) -> Result<StarknetTokenBridgeContractClient, Error> {
     // Deploy the Starknet Token Bridge contract (no explicit constructor)
     let (token_bridge_contract, token_bridge_contract_implementation) =
         deploy_contract_behind_proxy(
             client.clone(),
-            STARKNET_TOKEN_BRIDGE,
+            &options.artifact_source().load(&STARKNET_TOKEN_BRIDGE)?,
             NO_CONSTRUCTOR_ARG,
             ProxyVersion::SafeProxy5_0_0,
+            options,
         )
-        .await?;
+        .await
+        .context(ErrorContext::new().contract("StarknetTokenBridge"))?;
 
     Ok(StarknetTokenBridgeContractClient::new(
         token_bridge_contract.address(),
@@ -5,6 +5,11 @@ use std::process::Command;
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("cargo:rerun-if-changed=force");
 
+    // Artifacts are only needed when embedded, they can be loaded at runtime otherwise
+    if env::var_os("CARGO_FEATURE_EMBEDDED_ARTIFACTS").is_none() {
+        return Ok(());
+    }
+
     Command::new("git")
         .args(["submodule", "update", "--init", "--recursive"])
         .status()?;
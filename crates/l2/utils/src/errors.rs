@@ -1,12 +1,437 @@
-use ethers::contract::ContractError;
+use ethers::abi::{self, Abi, ParamType, Token};
+use ethers::contract::{ContractError, MulticallError};
 use ethers::middleware::Middleware;
-use ethers::providers::ProviderError;
+use ethers::providers::{ProviderError, RpcError};
+use ethers::signers::WalletError;
+use ethers::types::{Address, Bytes, TransactionReceipt, TxHash, U256};
+use std::fmt;
+use std::path::PathBuf;
 use thiserror::Error;
 
+use crate::registry::RegistryError;
+use crate::LocalWalletSignerMiddleware;
+
+/// Error of all the L2 crates: deployments, sandbox and contract clients.
+/// `M` is the middleware of the failing call, deployments use the local signer.
 #[derive(Debug, Error)]
-pub enum Error<M: Middleware> {
+pub enum Error<M: Middleware = LocalWalletSignerMiddleware> {
     #[error(transparent)]
     ContractError(#[from] ContractError<M>),
     #[error(transparent)]
     ProviderError(#[from] ProviderError),
+    #[error(transparent)]
+    MulticallError(#[from] MulticallError<M>),
+    #[error("Call {index} is not part of the batch, which has {len} results")]
+    BatchCallNotFound { index: usize, len: usize },
+    #[error("Transaction {:?} reverted: {reason:?}", .receipt.transaction_hash)]
+    Reverted {
+        receipt: Box<TransactionReceipt>,
+        reason: Option<RevertReason>,
+    },
+    #[error("Transaction {0:?} was dropped from the mempool")]
+    Dropped(TxHash),
+    #[error("Transaction {0:?} was not confirmed before the timeout")]
+    Timeout(TxHash),
+    #[error("{0:?} is not a StarkWare proxy: no implementation found")]
+    NotAProxy(Address),
+    #[error("{0:?} is a proxy without implementation")]
+    NoImplementation(Address),
+    #[error("Implementation {implementation:?} of proxy {proxy:?} has no code")]
+    ImplementationWithoutCode {
+        proxy: Address,
+        implementation: Address,
+    },
+    #[error("Governance of proxy {proxy:?} can't be handed over to {account:?}: {reason}")]
+    GovernanceHandover {
+        proxy: Address,
+        account: Address,
+        reason: &'static str,
+    },
+    // Build artifacts
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("['bytecode']['object'] is not a string")]
+    BytecodeObject,
+    #[error(transparent)]
+    Hex(#[from] hex::FromHexError),
+    #[error(transparent)]
+    Abi(#[from] abi::Error),
+    #[error("Invalid contract build artifacts: missing field `{0}`")]
+    ContractBuildArtifacts(&'static str),
+    #[error("Failed to read build artifacts from {0}: {1}")]
+    ArtifactRead(PathBuf, #[source] std::io::Error),
+    #[error(
+        "Build artifacts `{0}` are not embedded, enable the `embedded-artifacts` feature or use `ArtifactSource::directory`"
+    )]
+    ArtifactNotEmbedded(&'static str),
+    #[error("Contract `{0}` not found in the combined-json build artifacts")]
+    ArtifactContractNotFound(String),
+    #[error(
+        "Several contracts match `{0}` in the combined-json build artifacts, use a fully qualified name (`path:Name`)"
+    )]
+    AmbiguousArtifactContract(String),
+    #[error("Library `{0}` is not linked, set its address with `DeployOptions::library`")]
+    UnlinkedLibrary(String),
+    #[error("Invalid link reference of library `{0}`")]
+    InvalidLinkReference(String),
+    #[error(transparent)]
+    Registry(#[from] RegistryError),
+    #[error("Constructor arguments provided but the contract has no constructor")]
+    UnexpectedConstructorArgs,
+    // Ethereum client
+    #[error("Failed to parse URL")]
+    UrlParser,
+    #[error("Chain id mismatch: configured {expected}, RPC endpoint reports {actual}")]
+    ChainIdMismatch { expected: u64, actual: U256 },
+    #[error("Invalid private key: {0}")]
+    InvalidPrivateKey(WalletError),
+    #[error("Failed to decrypt keystore: {0}")]
+    Keystore(WalletError),
+    #[error("Keystore password environment variable `{0}` is not set")]
+    KeystorePasswordEnv(String),
+    #[error("Failed to read keystore password from {0}: {1}")]
+    KeystorePasswordFile(PathBuf, #[source] std::io::Error),
+    #[error("Invalid mnemonic or derivation path: {0}")]
+    Mnemonic(WalletError),
+    #[error("Anvil binary not found at {0}")]
+    AnvilNotFound(PathBuf),
+    #[error("Failed to spawn Anvil: {0}")]
+    AnvilSpawn(String),
+    /// Error of a step of a deployment or of a contract call, see [`ResultExt::context`].
+    /// The inner error is part of the message rather than the `source()`: a boxed source of the
+    /// same type would make the `std::error::Error` implementation recursive.
+    #[error("{context}: {}", .error)]
+    Context {
+        context: ErrorContext,
+        error: Box<Error<M>>,
+    },
+}
+
+impl<M: Middleware> Error<M> {
+    /// Error without the [`Error::Context`] layers
+    pub fn root(&self) -> &Error<M> {
+        match self {
+            Error::Context { error, .. } => error.root(),
+            _ => self,
+        }
+    }
+
+    /// Contexts of the error, from the outermost one (e.g. the deployment step) to the innermost one
+    pub fn contexts(&self) -> Vec<&ErrorContext> {
+        let mut contexts = Vec::new();
+        let mut error = self;
+        while let Error::Context {
+            context,
+            error: inner,
+        } = error
+        {
+            contexts.push(context);
+            error = inner;
+        }
+        contexts
+    }
+
+    /// Raw revert data if the call or the gas estimation of the transaction reverted
+    pub fn revert_data(&self) -> Option<Bytes> {
+        match self.root() {
+            Error::ContractError(e) => e.as_revert().cloned(),
+            Error::ProviderError(e) => e.as_error_response()?.as_revert_data(),
+            Error::MulticallError(e) => e.as_contract_error()?.as_revert().cloned(),
+            Error::Reverted {
+                reason: Some(RevertReason::Unknown(data)),
+                ..
+            } => Some(data.clone()),
+            _ => None,
+        }
+    }
+
+    /// Decoded revert reason if the call or the gas estimation of the transaction reverted.
+    /// Custom errors are only decoded by the contract clients, which know the ABI of the called
+    /// contract, they are [`RevertReason::Unknown`] here (see [`Self::revert_reason_with_abis`]).
+    pub fn revert_reason(&self) -> Option<RevertReason> {
+        self.revert_reason_with_abis(&[])
+    }
+
+    /// Same as [`Self::revert_reason`], custom errors being looked up in `abis`
+    /// (e.g. the `*_ABI` statics generated for the contract interfaces).
+    pub fn revert_reason_with_abis(&self, abis: &[&Abi]) -> Option<RevertReason> {
+        if let Error::Reverted {
+            reason: Some(reason),
+            ..
+        } = self.root()
+        {
+            if !matches!(reason, RevertReason::Unknown(_)) {
+                return Some(reason.clone());
+            }
+        }
+        self.revert_data()
+            .map(|data| RevertReason::decode(&data, abis))
+    }
+}
+
+/// Contract, method and address an error relates to, every field being optional
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ErrorContext {
+    pub contract: Option<String>,
+    pub method: Option<String>,
+    pub address: Option<Address>,
+}
+
+impl ErrorContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn contract(mut self, contract: impl Into<String>) -> Self {
+        self.contract = Some(contract.into());
+        self
+    }
+
+    pub fn method(mut self, method: impl Into<String>) -> Self {
+        self.method = Some(method.into());
+        self
+    }
+
+    pub fn address(mut self, address: Address) -> Self {
+        self.address = Some(address);
+        self
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.contract, &self.method) {
+            (Some(contract), Some(method)) => write!(f, "{contract}::{method}")?,
+            (Some(contract), None) => write!(f, "{contract}")?,
+            (None, Some(method)) => write!(f, "{method}")?,
+            (None, None) => write!(f, "<unknown>")?,
+        }
+        if let Some(address) = self.address {
+            write!(f, " at {address:?}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Attaches an [`ErrorContext`] to the error of a result
+pub trait ResultExt<T, M: Middleware> {
+    fn context(self, context: ErrorContext) -> Result<T, Error<M>>;
+}
+
+impl<T, M: Middleware> ResultExt<T, M> for Result<T, Error<M>> {
+    fn context(self, context: ErrorContext) -> Result<T, Error<M>> {
+        self.map_err(|error| Error::Context {
+            context,
+            error: Box::new(error),
+        })
+    }
+}
+
+/// Selector of `Error(string)`, used by `require` and `revert` with a message
+const ERROR_STRING_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of `Panic(uint256)`, used by failing asserts, overflows, ...
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+macro_rules! starkware_revert_reasons {
+    ($($variant:ident => $reason:literal,)*) => {
+        /// Revert reason of a StarkWare contract call
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum RevertReason {
+            $($variant,)*
+            /// Require string not known by zaun
+            Other(String),
+            /// Solidity custom error found in the ABIs provided for decoding
+            Custom { name: String, args: Vec<Token> },
+            /// Failed assertion, arithmetic overflow, ... (`Panic(uint256)` error code)
+            Panic(U256),
+            /// Revert data that could not be decoded (no reason, unknown custom error)
+            Unknown(Bytes),
+        }
+
+        impl RevertReason {
+            /// Maps a require string to its variant
+            pub fn from_reason(reason: &str) -> Self {
+                match reason {
+                    $($reason => Self::$variant,)*
+                    other => Self::Other(other.to_owned()),
+                }
+            }
+
+            /// Require string of the revert reason, if any
+            pub fn reason(&self) -> Option<&str> {
+                match self {
+                    $(Self::$variant => Some($reason),)*
+                    Self::Other(reason) => Some(reason),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+starkware_revert_reasons! {
+    // Governance
+    OnlyGovernance => "ONLY_GOVERNANCE",
+    OnlyCandidateGovernor => "ONLY_CANDIDATE_GOVERNOR",
+    AlreadyGovernor => "ALREADY_GOVERNOR",
+    NotGovernor => "NOT_GOVERNOR",
+    GovernorSelfRemove => "GOVERNOR_SELF_REMOVE",
+    AlreadyInitialized => "ALREADY_INITIALIZED",
+    Finalized => "FINALIZED",
+    OnlyOperator => "ONLY_OPERATOR",
+    // Core contract state update
+    InvalidConfigHash => "INVALID_CONFIG_HASH",
+    FullOutputNotSupported => "FULL_OUTPUT_NOT_SUPPORTED",
+    AggregatorModeInvalidOsProgramHash => "AGGREGATOR_MODE_INVALID_OS_PROGRAM_HASH",
+    NoStateTransitionProof => "NO_STATE_TRANSITION_PROOF",
+    StarknetOutputTooShort => "STARKNET_OUTPUT_TOO_SHORT",
+    StarknetOutputTooLong => "STARKNET_OUTPUT_TOO_LONG",
+    UnexpectedKzgDaFlag => "UNEXPECTED_KZG_DA_FLAG",
+    ProgramOutputValueOutOfRange => "PROGRAM_OUTPUT_VALUE_OUT_OF_RANGE",
+    InvalidPrevBlockNumber => "INVALID_PREV_BLOCK_NUMBER",
+    InvalidNewBlockNumber => "INVALID_NEW_BLOCK_NUMBER",
+    InvalidPrevBlockHash => "INVALID_PREV_BLOCK_HASH",
+    InvalidPreviousRoot => "INVALID_PREVIOUS_ROOT",
+    ReentrancyFailure => "REENTRANCY_FAILURE",
+    // Messaging
+    InvalidMessageSegmentSize => "INVALID_MESSAGE_SEGMENT_SIZE",
+    MessageTooShort => "MESSAGE_TOO_SHORT",
+    InvalidPayloadLength => "INVALID_PAYLOAD_LENGTH",
+    TruncatedMessagePayload => "TRUNCATED_MESSAGE_PAYLOAD",
+    InvalidMessageToConsume => "INVALID_MESSAGE_TO_CONSUME",
+    MaxL1ToL2MessageFeeExceeded => "MAX_L1_TO_L2_MESSAGE_FEE_EXCEEDED",
+    NoMessageToCancel => "NO_MESSAGE_TO_CANCEL",
+    MessageCancellationNotRequested => "MESSAGE_CANCELLATION_NOT_REQUESTED",
+    MessageCancellationNotAllowedYet => "MESSAGE_CANCELLATION_NOT_ALLOWED_YET",
+    EthTransferFailed => "ETH_TRANSFER_FAILED",
+    // Bridges
+    TokenNotServiced => "TOKEN_NOT_SERVICED",
+    ZeroDeposit => "ZERO_DEPOSIT",
+    MaxBalanceExceeded => "MAX_BALANCE_EXCEEDED",
+    L2AddressOutOfRange => "L2_ADDRESS_OUT_OF_RANGE",
+}
+
+impl RevertReason {
+    /// Decodes revert data, custom errors being looked up in `abis`
+    pub fn decode(data: &[u8], abis: &[&Abi]) -> Self {
+        let Some((selector, args)) = data.split_first_chunk::<4>() else {
+            return Self::Unknown(data.to_vec().into());
+        };
+
+        match *selector {
+            ERROR_STRING_SELECTOR => {
+                if let Ok(Some(Token::String(reason))) =
+                    abi::decode(&[ParamType::String], args).map(|tokens| tokens.into_iter().next())
+                {
+                    return Self::from_reason(&reason);
+                }
+            }
+            PANIC_SELECTOR => {
+                if let Ok(Some(Token::Uint(code))) = abi::decode(&[ParamType::Uint(256)], args)
+                    .map(|tokens| tokens.into_iter().next())
+                {
+                    return Self::Panic(code);
+                }
+            }
+            _ => {
+                let custom_error = abis
+                    .iter()
+                    .flat_map(|abi| abi.errors())
+                    .find(|error| error.signature()[..4] == *selector);
+                if let Some(error) = custom_error {
+                    if let Ok(args) = error.decode(args) {
+                        return Self::Custom {
+                            name: error.name.clone(),
+                            args,
+                        };
+                    }
+                }
+            }
+        }
+        Self::Unknown(data.to_vec().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, ErrorContext, ResultExt, RevertReason};
+    use ethers::abi::{AbiEncode, Token};
+    use ethers::contract::ContractError;
+    use ethers::types::{Address, U256};
+
+    #[test]
+    fn test_decode_require_string() {
+        let mut data = vec![0x08, 0xc3, 0x79, 0xa0];
+        data.extend(ethers::abi::encode(&[Token::String(
+            "ONLY_OPERATOR".into(),
+        )]));
+        assert_eq!(RevertReason::decode(&data, &[]), RevertReason::OnlyOperator);
+
+        let mut data = vec![0x08, 0xc3, 0x79, 0xa0];
+        data.extend(ethers::abi::encode(&[Token::String(
+            "SOMETHING_ELSE".into(),
+        )]));
+        assert_eq!(
+            RevertReason::decode(&data, &[]),
+            RevertReason::Other("SOMETHING_ELSE".into())
+        );
+    }
+
+    #[test]
+    fn test_decode_panic() {
+        let mut data = vec![0x4e, 0x48, 0x7b, 0x71];
+        data.extend(U256::from(0x11).encode());
+        assert_eq!(
+            RevertReason::decode(&data, &[]),
+            RevertReason::Panic(U256::from(0x11))
+        );
+    }
+
+    #[test]
+    fn test_decode_custom_error() {
+        let abi =
+            ethers::abi::parse_abi(&["error AccessDenied(address account, uint256 role)"]).unwrap();
+        let account = Address::repeat_byte(0x11);
+        let mut data = abi.errors().next().unwrap().signature()[..4].to_vec();
+        data.extend(ethers::abi::encode(&[
+            Token::Address(account),
+            Token::Uint(2.into()),
+        ]));
+        let error: Error = Error::ContractError(ContractError::Revert(data.into()));
+
+        assert!(matches!(
+            error.revert_reason(),
+            Some(RevertReason::Unknown(_))
+        ));
+        assert_eq!(
+            error.revert_reason_with_abis(&[&abi]),
+            Some(RevertReason::Custom {
+                name: "AccessDenied".into(),
+                args: vec![Token::Address(account), Token::Uint(2.into())],
+            })
+        );
+    }
+
+    #[test]
+    fn test_error_context_chain() {
+        let address = Address::repeat_byte(0x11);
+        let result: Result<(), Error> = Err(Error::UnexpectedConstructorArgs);
+        let error = result
+            .context(ErrorContext::new().method("deploy implementation"))
+            .context(ErrorContext::new().contract("Starknet").address(address))
+            .unwrap_err();
+
+        assert_eq!(error.contexts().len(), 2);
+        assert!(matches!(error.root(), Error::UnexpectedConstructorArgs));
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Starknet at {address:?}: deploy implementation: \
+                 Constructor arguments provided but the contract has no constructor"
+            )
+        );
+
+        let error: Box<dyn std::error::Error> = Box::new(error);
+        assert!(error.source().is_none());
+    }
 }
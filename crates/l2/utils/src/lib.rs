@@ -1,16 +1,77 @@
 pub mod errors;
+pub mod multicall;
+pub mod registry;
+pub mod safe;
+pub mod transaction;
 use ethers::prelude::SignerMiddleware;
-use ethers::providers::{Http, Provider};
+use ethers::providers::{Http, Ipc, Provider, Ws};
 use ethers::signers::LocalWallet;
 use ethers::types::Address;
 use std::sync::Arc;
 
 pub type LocalWalletSignerMiddleware = SignerMiddleware<Provider<Http>, LocalWallet>;
 
+/// Same as [`LocalWalletSignerMiddleware`] over a WebSocket transport (required for subscriptions).
+pub type WsLocalWalletSignerMiddleware = SignerMiddleware<Provider<Ws>, LocalWallet>;
+
+/// Same as [`LocalWalletSignerMiddleware`] over an IPC transport (local node only).
+pub type IpcLocalWalletSignerMiddleware = SignerMiddleware<Provider<Ipc>, LocalWallet>;
+
+/// Provider sending unsigned transactions on behalf of an account impersonated
+/// by the node (`anvil_impersonateAccount`), no private key required.
+pub type ImpersonatedMiddleware = Provider<Http>;
+
 pub const NO_CONSTRUCTOR_ARG: () = ();
 
-pub trait StarknetContractClient {
+/// Common accessors of the contract clients, `M` being the middleware the client was built with
+/// (any [`ethers::providers::Middleware`], HTTP signer by default).
+pub trait StarknetContractClient<M = LocalWalletSignerMiddleware> {
     fn address(&self) -> Address;
     fn implementation_address(&self) -> Address;
-    fn client(&self) -> Arc<LocalWalletSignerMiddleware>;
+    fn client(&self) -> Arc<M>;
+}
+
+/// Gives access to contract `C` for its view methods (`*ViewTrait` of the interfaces).
+/// Implemented by every [`ReadWrite`] client holding `C` and by [`ReadOnly`] clients.
+pub trait ContractReader<C> {
+    fn reader(&self) -> &C;
+}
+
+/// Marker of the clients exposing both the view and the state-changing methods of their contracts.
+pub trait ReadWrite {}
+
+impl<T: AsRef<C> + ReadWrite, C> ContractReader<C> for T {
+    fn reader(&self) -> &C {
+        self.as_ref()
+    }
+}
+
+/// Read-only wrapper around a contract client, typically built over a plain `Provider` with no signer.
+/// Only the view methods of the wrapped client are available, calls to state-changing methods
+/// do not compile.
+#[derive(Clone)]
+pub struct ReadOnly<C>(C);
+
+impl<C> ReadOnly<C> {
+    pub fn new(client: C) -> Self {
+        Self(client)
+    }
+}
+
+impl<C: AsRef<T>, T> ContractReader<T> for ReadOnly<C> {
+    fn reader(&self) -> &T {
+        self.0.as_ref()
+    }
+}
+
+impl<M, C: StarknetContractClient<M>> StarknetContractClient<M> for ReadOnly<C> {
+    fn address(&self) -> Address {
+        self.0.address()
+    }
+    fn implementation_address(&self) -> Address {
+        self.0.implementation_address()
+    }
+    fn client(&self) -> Arc<M> {
+        self.0.client()
+    }
 }
@@ -0,0 +1,133 @@
+use ethers::abi::{Detokenize, Token};
+use ethers::contract::{ContractCall, ContractError, Multicall, MULTICALL_ADDRESS};
+use ethers::middleware::Middleware;
+use ethers::types::{Address, BlockNumber, Bytes};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use crate::errors::Error;
+
+/// Handle of a call added to a [`ViewBatch`], used to get its typed result back
+#[derive(Debug)]
+pub struct BatchedCall<D> {
+    index: usize,
+    output: PhantomData<D>,
+}
+
+impl<D> Clone for BatchedCall<D> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<D> Copy for BatchedCall<D> {}
+
+/// Batches view calls of any of the contract clients into a single Multicall3 `aggregate3` call.
+/// Calls are built with the `*_call` methods of the view traits, e.g.
+/// `batch.add(core_contract.state_root_call())`.
+pub struct ViewBatch<M: Middleware> {
+    multicall: Multicall<M>,
+    len: usize,
+}
+
+impl<M: Middleware> ViewBatch<M> {
+    /// Batch using Multicall3 at its canonical address
+    pub fn new(client: Arc<M>) -> Result<Self, Error<M>> {
+        Self::at(client, MULTICALL_ADDRESS)
+    }
+
+    /// Batch using a Multicall3 deployment at a custom address
+    pub fn at(client: Arc<M>, address: Address) -> Result<Self, Error<M>> {
+        Ok(Self {
+            multicall: Multicall::new_with_chain_id(client, Some(address), None::<u64>)?,
+            len: 0,
+        })
+    }
+
+    /// Block the calls are executed at, latest by default
+    pub fn block(mut self, block: impl Into<BlockNumber>) -> Self {
+        self.multicall = self.multicall.block(block);
+        self
+    }
+
+    /// Adds a call to the batch, a revert only fails this call
+    pub fn add<D: Detokenize>(&mut self, call: ContractCall<M, D>) -> BatchedCall<D> {
+        self.multicall.add_call(call, true);
+        self.len += 1;
+        BatchedCall {
+            index: self.len - 1,
+            output: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Executes all the calls with a single `eth_call`
+    pub async fn call(&self) -> Result<BatchResults<M>, Error<M>> {
+        Ok(BatchResults {
+            results: self.multicall.call_raw().await?,
+            middleware: PhantomData,
+        })
+    }
+}
+
+/// Raw results of a [`ViewBatch`], decoded one by one with [`BatchResults::get`]
+#[derive(Debug)]
+pub struct BatchResults<M> {
+    results: Vec<Result<Token, Bytes>>,
+    middleware: PhantomData<M>,
+}
+
+impl<M: Middleware> BatchResults<M> {
+    /// Typed result of a batched call, a reverted call returns its revert data
+    /// (see [`Error::revert_reason`]). Fails with [`Error::BatchCallNotFound`] if `call`
+    /// was added to another batch.
+    pub fn get<D: Detokenize>(&self, call: BatchedCall<D>) -> Result<D, Error<M>> {
+        let result = self
+            .results
+            .get(call.index)
+            .ok_or(Error::BatchCallNotFound {
+                index: call.index,
+                len: self.results.len(),
+            })?;
+        match result {
+            Ok(token) => D::from_tokens(vec![token.clone()])
+                .map_err(|e| ContractError::DetokenizationError(e).into()),
+            Err(revert_data) => Err(ContractError::Revert(revert_data.clone()).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::U256;
+
+    #[test]
+    fn test_batch_results_get() {
+        let results = BatchResults::<crate::LocalWalletSignerMiddleware> {
+            results: vec![Ok(Token::Uint(7.into())), Err(Bytes::from(vec![0x08]))],
+            middleware: PhantomData,
+        };
+        let call = |index| BatchedCall::<U256> {
+            index,
+            output: PhantomData,
+        };
+
+        assert_eq!(results.get(call(0)).unwrap(), U256::from(7));
+        assert!(matches!(
+            results.get(call(1)),
+            Err(Error::ContractError(ContractError::Revert(_)))
+        ));
+        assert!(matches!(
+            results.get(call(2)),
+            Err(Error::BatchCallNotFound { index: 2, len: 2 })
+        ));
+    }
+}
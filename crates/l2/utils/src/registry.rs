@@ -0,0 +1,211 @@
+use ethers::types::{Address, TxHash, H256};
+use ethers::utils::to_checksum;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RegistryError {
+    #[error("Failed to access the deployment registry {0}: {1}")]
+    Io(PathBuf, #[source] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid deployment registry entry `{0}`: invalid field `{1}`")]
+    InvalidEntry(String, &'static str),
+}
+
+/// Contract recorded in a [`DeploymentRegistry`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeploymentEntry {
+    pub name: String,
+    /// Address the clients are built with, the proxy for contracts deployed behind one
+    pub address: Address,
+    /// Implementation behind the proxy, same as `address` otherwise
+    pub implementation: Address,
+    /// Contract name of the proxy (e.g. `Proxy_5_0_0`), `None` if not deployed behind one
+    pub proxy_version: Option<String>,
+    /// Hash of the creation bytecode (before library linking) of the implementation, keccak256
+    /// of its lowercase hex text without `0x` prefix (see `ContractArtifact::hash`)
+    pub artifact_hash: H256,
+    /// Transaction deploying `address`, `None` if it was already deployed (CREATE2)
+    pub tx_hash: Option<TxHash>,
+    pub block_number: Option<u64>,
+}
+
+impl DeploymentEntry {
+    fn to_json(&self) -> Value {
+        json!({
+            "address": to_checksum(&self.address, None),
+            "implementation": to_checksum(&self.implementation, None),
+            "proxy_version": self.proxy_version,
+            "artifact_hash": self.artifact_hash,
+            "tx_hash": self.tx_hash,
+            "block_number": self.block_number,
+        })
+    }
+
+    fn from_json(name: &str, entry: &Value) -> Result<Self, RegistryError> {
+        let invalid = |field| RegistryError::InvalidEntry(name.to_owned(), field);
+        let block_number = match entry.get("block_number") {
+            None | Some(Value::Null) => None,
+            Some(value) => Some(value.as_u64().ok_or_else(|| invalid("block_number"))?),
+        };
+
+        Ok(Self {
+            name: name.to_owned(),
+            address: parse_field(name, entry, "address")?.ok_or_else(|| invalid("address"))?,
+            implementation: parse_field(name, entry, "implementation")?
+                .ok_or_else(|| invalid("implementation"))?,
+            proxy_version: parse_field(name, entry, "proxy_version")?,
+            artifact_hash: parse_field(name, entry, "artifact_hash")?
+                .ok_or_else(|| invalid("artifact_hash"))?,
+            tx_hash: parse_field(name, entry, "tx_hash")?,
+            block_number,
+        })
+    }
+}
+
+/// Parses a string field of an entry, `None` if it is missing or `null`
+fn parse_field<T: FromStr>(
+    name: &str,
+    entry: &Value,
+    field: &'static str,
+) -> Result<Option<T>, RegistryError> {
+    match entry.get(field) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_str()
+            .and_then(|value| value.parse().ok())
+            .map(Some)
+            .ok_or_else(|| RegistryError::InvalidEntry(name.to_owned(), field)),
+    }
+}
+
+/// Address book of the deployed contracts, keyed by contract name, so that a later run
+/// can build its clients from the same deployment (`from_registry_entry` of the clients).
+/// Clones share the same entries, recording an entry saves the JSON file if any.
+#[derive(Debug, Clone, Default)]
+pub struct DeploymentRegistry {
+    path: Option<PathBuf>,
+    entries: Arc<Mutex<BTreeMap<String, DeploymentEntry>>>,
+}
+
+impl DeploymentRegistry {
+    /// Registry persisted to the JSON file at `path`, loaded from it if it exists
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, RegistryError> {
+        let path = path.into();
+        let entries = match std::fs::read(&path) {
+            Ok(contents) => parse_entries(&serde_json::from_slice(&contents)?)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(RegistryError::Io(path, e)),
+        };
+
+        Ok(Self {
+            path: Some(path),
+            entries: Arc::new(Mutex::new(entries)),
+        })
+    }
+
+    /// Registry kept in memory only
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Records a deployed contract, replacing the entry with the same name
+    /// (a warning is logged if it was another deployment)
+    pub fn record(&self, entry: DeploymentEntry) -> Result<(), RegistryError> {
+        if let Some(replaced) = self.lock().insert(entry.name.clone(), entry.clone()) {
+            if replaced.address != entry.address {
+                log::warn!(
+                    "⚠️  Registry entry `{}` at {:?} replaced by the deployment at {:?}",
+                    entry.name,
+                    replaced.address,
+                    entry.address
+                );
+            }
+        }
+        self.save()
+    }
+
+    pub fn get(&self, name: &str) -> Option<DeploymentEntry> {
+        self.lock().get(name).cloned()
+    }
+
+    pub fn entries(&self) -> Vec<DeploymentEntry> {
+        self.lock().values().cloned().collect()
+    }
+
+    pub fn to_json(&self) -> Value {
+        let entries = self
+            .lock()
+            .iter()
+            .map(|(name, entry)| (name.clone(), entry.to_json()))
+            .collect::<Map<_, _>>();
+        Value::Object(entries)
+    }
+
+    /// Writes the registry to its JSON file, nothing to do for an in memory registry
+    pub fn save(&self) -> Result<(), RegistryError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let json = serde_json::to_string_pretty(&self.to_json())?;
+        std::fs::write(path, json).map_err(|e| RegistryError::Io(path.clone(), e))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, DeploymentEntry>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn parse_entries(json: &Value) -> Result<BTreeMap<String, DeploymentEntry>, RegistryError> {
+    json.as_object()
+        .ok_or_else(|| RegistryError::InvalidEntry("<root>".to_owned(), "entries"))?
+        .iter()
+        .map(|(name, entry)| Ok((name.clone(), DeploymentEntry::from_json(name, entry)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_json_roundtrip() {
+        let registry = DeploymentRegistry::in_memory();
+        let entry = DeploymentEntry {
+            name: "StarkgateManager".to_owned(),
+            address: Address::repeat_byte(0x11),
+            implementation: Address::repeat_byte(0x22),
+            proxy_version: Some("Proxy_5_0_0".to_owned()),
+            artifact_hash: H256::repeat_byte(0x33),
+            tx_hash: Some(TxHash::repeat_byte(0x44)),
+            block_number: Some(42),
+        };
+        registry.record(entry.clone()).unwrap();
+
+        let entries = parse_entries(&registry.to_json()).unwrap();
+        assert_eq!(entries.get("StarkgateManager"), Some(&entry));
+    }
+
+    #[test]
+    fn test_registry_not_an_object() {
+        assert!(matches!(
+            parse_entries(&json!([])),
+            Err(RegistryError::InvalidEntry(_, "entries"))
+        ));
+        assert!(matches!(
+            parse_entries(&json!({ "StarkgateManager": 1 })),
+            Err(RegistryError::InvalidEntry(_, "address"))
+        ));
+    }
+}
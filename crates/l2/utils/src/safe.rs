@@ -0,0 +1,164 @@
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::Address;
+use ethers::utils::to_checksum;
+use serde_json::{json, Value};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// Version of the Safe Transaction Builder batch file format
+const SAFE_BATCH_VERSION: &str = "1.0";
+
+#[derive(Debug, Error)]
+pub enum SafeBatchError {
+    #[error("Transaction {0} has no target address (contract deployments can't be batched)")]
+    MissingTarget(usize),
+    #[error("Failed to write the batch file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// Batch of transactions exported in the Safe Transaction Builder JSON format,
+/// to be imported and signed from the Safe owning the governance roles.
+/// Transactions are typically built with the `build_*` variants of the trait methods.
+#[derive(Debug, Clone)]
+pub struct SafeTransactionBatch {
+    chain_id: u64,
+    name: String,
+    description: String,
+    safe_address: Option<Address>,
+    transactions: Vec<TypedTransaction>,
+}
+
+impl SafeTransactionBatch {
+    pub fn new(chain_id: u64) -> Self {
+        Self {
+            chain_id,
+            name: "Transactions Batch".to_owned(),
+            description: String::new(),
+            safe_address: None,
+            transactions: Vec::new(),
+        }
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// Safe the batch is meant for, checked by the Transaction Builder on import
+    pub fn safe_address(mut self, safe_address: Address) -> Self {
+        self.safe_address = Some(safe_address);
+        self
+    }
+
+    /// Appends a transaction, only its target, calldata and value are exported
+    pub fn transaction(mut self, tx: TypedTransaction) -> Self {
+        self.transactions.push(tx);
+        self
+    }
+
+    pub fn to_json(&self) -> Result<Value, SafeBatchError> {
+        let transactions = self
+            .transactions
+            .iter()
+            .enumerate()
+            .map(|(index, tx)| {
+                let to = tx.to_addr().ok_or(SafeBatchError::MissingTarget(index))?;
+                Ok(json!({
+                    "to": to_checksum(to, None),
+                    "value": tx.value().copied().unwrap_or_default().to_string(),
+                    "data": tx.data().cloned().unwrap_or_default(),
+                    "contractMethod": null,
+                    "contractInputsValues": null,
+                }))
+            })
+            .collect::<Result<Vec<_>, SafeBatchError>>()?;
+
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or_default();
+
+        Ok(json!({
+            "version": SAFE_BATCH_VERSION,
+            "chainId": self.chain_id.to_string(),
+            "createdAt": created_at,
+            "meta": {
+                "name": self.name,
+                "description": self.description,
+                "createdFromSafeAddress": self
+                    .safe_address
+                    .map(|address| to_checksum(&address, None))
+                    .unwrap_or_default(),
+            },
+            "transactions": transactions,
+        }))
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), SafeBatchError> {
+        let json = serde_json::to_string_pretty(&self.to_json()?)?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::{Bytes, TransactionRequest};
+
+    #[test]
+    fn test_batch_json_format() {
+        let safe = Address::repeat_byte(0x5a);
+        let target = Address::repeat_byte(0xab);
+        let tx = TransactionRequest::new()
+            .to(target)
+            .value(1000)
+            .data(Bytes::from(vec![0x12, 0x34]));
+        let json = SafeTransactionBatch::new(5)
+            .name("Upgrade")
+            .description("Upgrade the core contract")
+            .safe_address(safe)
+            .transaction(tx.into())
+            .to_json()
+            .unwrap();
+
+        assert_eq!(json["version"], "1.0");
+        assert_eq!(json["chainId"], "5");
+        assert!(json["createdAt"].is_u64());
+        assert_eq!(
+            json["meta"],
+            json!({
+                "name": "Upgrade",
+                "description": "Upgrade the core contract",
+                "createdFromSafeAddress": to_checksum(&safe, None),
+            })
+        );
+        assert_eq!(
+            json["transactions"],
+            json!([{
+                "to": to_checksum(&target, None),
+                "value": "1000",
+                "data": "0x1234",
+                "contractMethod": null,
+                "contractInputsValues": null,
+            }])
+        );
+    }
+
+    #[test]
+    fn test_batch_without_target() {
+        let err = SafeTransactionBatch::new(1)
+            .transaction(TransactionRequest::new().into())
+            .to_json()
+            .unwrap_err();
+        assert!(matches!(err, SafeBatchError::MissingTarget(0)));
+    }
+}
@@ -0,0 +1,198 @@
+use ethers::abi::Detokenize;
+use ethers::contract::{Contract, ContractCall};
+use ethers::middleware::Middleware;
+use ethers::providers::{spoof, RawCall};
+use ethers::types::{Address, BlockId, TransactionReceipt, TxHash, U256, U64};
+
+use crate::errors::{Error, ErrorContext, ResultExt, RevertReason};
+
+/// Outcome of a state-changing contract call once its transaction is no longer pending
+#[must_use = "a reverted or dropped transaction is not an error, check the outcome"]
+#[derive(Debug, Clone)]
+pub enum TransactionOutcome {
+    /// Mined with a successful status
+    Success(TransactionReceipt),
+    /// Mined but reverted, the reason being decoded by replaying the call
+    /// against the state of the previous block (`None` if the replay did not revert),
+    /// custom errors with the ABI of the called contract
+    Reverted {
+        receipt: TransactionReceipt,
+        reason: Option<RevertReason>,
+    },
+    /// Dropped from the mempool before being mined
+    Dropped(TxHash),
+}
+
+impl TransactionOutcome {
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Success(_))
+    }
+
+    pub fn tx_hash(&self) -> TxHash {
+        match self {
+            Self::Success(receipt) | Self::Reverted { receipt, .. } => receipt.transaction_hash,
+            Self::Dropped(tx_hash) => *tx_hash,
+        }
+    }
+
+    /// Receipt of the mined transaction, successful or not
+    pub fn receipt(&self) -> Option<&TransactionReceipt> {
+        match self {
+            Self::Success(receipt) | Self::Reverted { receipt, .. } => Some(receipt),
+            Self::Dropped(_) => None,
+        }
+    }
+
+    pub fn gas_used(&self) -> Option<U256> {
+        self.receipt().and_then(|receipt| receipt.gas_used)
+    }
+
+    /// Receipt of the successful transaction, reverted and dropped transactions being errors
+    pub fn into_receipt<M: Middleware>(self) -> Result<TransactionReceipt, Error<M>> {
+        match self {
+            Self::Success(receipt) => Ok(receipt),
+            Self::Reverted { receipt, reason } => Err(Error::Reverted {
+                receipt: Box::new(receipt),
+                reason,
+            }),
+            Self::Dropped(tx_hash) => Err(Error::Dropped(tx_hash)),
+        }
+    }
+}
+
+/// Sends the transaction of a state-changing call of `contract` and waits for its outcome
+pub async fn send_call<M: Middleware, D: Detokenize>(
+    contract: &Contract<M>,
+    call: ContractCall<M, D>,
+) -> Result<TransactionOutcome, Error<M>> {
+    let context = call_context(&call);
+    let pending = call
+        .send()
+        .await
+        .map_err(Error::from)
+        .context(context.clone())?;
+    let tx_hash = pending.tx_hash();
+    let Some(receipt) = pending.await.map_err(Error::from).context(context)? else {
+        return Ok(TransactionOutcome::Dropped(tx_hash));
+    };
+
+    if receipt.status == Some(U64::one()) {
+        return Ok(TransactionOutcome::Success(receipt));
+    }
+
+    let mut replay = call.from(receipt.from);
+    if let Some(block_number) = receipt.block_number {
+        replay = replay.block(block_number.saturating_sub(U64::one()));
+    }
+    let reason = match replay.call_raw_bytes().await {
+        Ok(_) => None,
+        Err(err) => Error::<M>::from(err).revert_reason_with_abis(&[contract.abi()]),
+    };
+    log::debug!("⚠️  Transaction {:?} reverted: {:?}", tx_hash, reason);
+
+    Ok(TransactionOutcome::Reverted { receipt, reason })
+}
+
+/// Options of the `simulate_*` variants of the state-changing trait methods
+#[derive(Debug, Clone, Default)]
+pub struct SimulationOptions {
+    block: Option<BlockId>,
+    from: Option<Address>,
+    state_overrides: Option<spoof::State>,
+}
+
+impl SimulationOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Block the call is simulated at, latest by default
+    pub fn block(mut self, block: impl Into<BlockId>) -> Self {
+        self.block = Some(block.into());
+        self
+    }
+
+    /// Sender of the simulated call, the default sender of the client (its signer account)
+    /// if not set, e.g. a multisig governor which can't sign with the client.
+    pub fn from(mut self, from: Address) -> Self {
+        self.from = Some(from);
+        self
+    }
+
+    /// State overrides (balances, code, storage) applied to the simulated call only.
+    /// The gas is estimated without them.
+    pub fn state_overrides(mut self, state_overrides: spoof::State) -> Self {
+        self.state_overrides = Some(state_overrides);
+        self
+    }
+}
+
+/// Result of a simulated state-changing contract call
+#[derive(Debug, Clone)]
+pub enum SimulationOutcome<D> {
+    /// The call would succeed, `gas_estimate` is `None` if the node could not estimate it
+    Success {
+        output: D,
+        gas_estimate: Option<U256>,
+    },
+    /// The call would revert
+    Reverted(RevertReason),
+}
+
+impl<D> SimulationOutcome<D> {
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Success { .. })
+    }
+}
+
+/// Simulates a state-changing call of `contract` with `eth_call`, nothing is broadcast.
+/// The call is sent from the default sender of the client of `contract` unless
+/// [`SimulationOptions::from`] is set: `eth_call` bypasses the signer, which would otherwise
+/// leave the sender unset. Custom errors are decoded with the ABI of `contract`.
+pub async fn simulate_call<M: Middleware, D: Detokenize>(
+    contract: &Contract<M>,
+    mut call: ContractCall<M, D>,
+    options: &SimulationOptions,
+) -> Result<SimulationOutcome<D>, Error<M>> {
+    let context = call_context(&call);
+    let from = options
+        .from
+        .or_else(|| call.tx.from().copied())
+        .or_else(|| contract.client().default_sender());
+    if let Some(from) = from {
+        call = call.from(from);
+    }
+    if let Some(block) = options.block {
+        call = call.block(block);
+    }
+
+    let raw_call = call.call_raw();
+    let result = match &options.state_overrides {
+        Some(state_overrides) => raw_call.state(state_overrides).await,
+        None => raw_call.await,
+    };
+    let output = match result {
+        Ok(output) => output,
+        Err(err) => {
+            let err = Error::from(err);
+            return match err.revert_reason_with_abis(&[contract.abi()]) {
+                Some(reason) => Ok(SimulationOutcome::Reverted(reason)),
+                None => Err(err).context(context),
+            };
+        }
+    };
+
+    Ok(SimulationOutcome::Success {
+        output,
+        gas_estimate: call.estimate_gas().await.ok(),
+    })
+}
+
+/// Method and address of a contract call, attached to its errors
+fn call_context<M: Middleware, D>(call: &ContractCall<M, D>) -> ErrorContext {
+    let context = ErrorContext::new().method(call.function.name.clone());
+    match call.tx.to_addr() {
+        Some(address) => context.address(*address),
+        None => context,
+    }
+}